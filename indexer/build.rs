@@ -0,0 +1,121 @@
+// Generates the `sealmeet` Move bindings from the local Move package sources
+//
+// The bindings describe struct layouts only and carry no package address, so the same
+// build decodes any deployment; `--suimeet-package-id` decides which one is indexed.
+
+#[path = "build/move_sources.rs"]
+mod move_sources;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use move_sources::{read_move_structs, MoveStruct};
+
+const SEALMEET_SOURCES: &str = "../sealmeet/sources";
+
+fn main() {
+    println!("cargo:rerun-if-changed={SEALMEET_SOURCES}");
+    println!("cargo:rerun-if-changed=build/move_sources.rs");
+
+    let sources = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(SEALMEET_SOURCES);
+    let structs = read_move_structs(&sources)
+        .unwrap_or_else(|e| panic!("Failed to read Move sources in {}: {e}", sources.display()));
+
+    let code = generate(&structs).unwrap_or_else(|errors| {
+        for error in &errors {
+            println!("cargo:warning={error}");
+        }
+        panic!("Cannot generate the sealmeet bindings:\n{}", errors.join("\n"))
+    });
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("sealmeet.rs");
+    fs::write(&out, code).unwrap();
+}
+
+/// The bindings, or one error per field whose type has no Rust equivalent
+fn generate(structs: &[MoveStruct]) -> Result<String, Vec<String>> {
+    // Struct name -> module, to resolve types declared in a sibling module
+    let modules: BTreeMap<&str, &str> = structs
+        .iter()
+        .map(|s| (s.name.as_str(), s.module.as_str()))
+        .collect();
+
+    let mut by_module: BTreeMap<&str, Vec<&MoveStruct>> = BTreeMap::new();
+    for s in structs {
+        by_module.entry(s.module.as_str()).or_default().push(s);
+    }
+
+    let mut errors = Vec::new();
+    let mut code = String::from(
+        "// @generated by build.rs from sealmeet/sources - do not edit\n\n\
+         /// `0x2::table::Table` layout; keys and values live in dynamic fields\n\
+         #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]\n\
+         pub struct Table {\n    pub id: move_types::ObjectId,\n    pub size: u64,\n}\n",
+    );
+
+    for (module, structs) in by_module {
+        code.push_str(&format!("\npub mod {module} {{\n"));
+        for s in structs {
+            code.push_str(&format!(
+                "\n    /// `{module}::{}` (has {})\n    \
+                 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]\n    \
+                 pub struct {} {{\n",
+                s.name,
+                s.abilities.join(", "),
+                s.name
+            ));
+
+            // The Move compiler gives field-less structs a `dummy_field: bool`
+            if s.fields.is_empty() {
+                code.push_str("        pub dummy_field: bool,\n");
+            }
+            for (field, type_) in &s.fields {
+                match rust_type(type_, &modules) {
+                    Ok(rust_type) => code.push_str(&format!("        pub {field}: {rust_type},\n")),
+                    Err(unsupported) => errors.push(format!(
+                        "{module}::{}.{field}: Move type `{unsupported}` is not supported by the binding generator",
+                        s.name
+                    )),
+                }
+            }
+            code.push_str("    }\n");
+        }
+        code.push_str("}\n");
+    }
+
+    if errors.is_empty() {
+        Ok(code)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Rust type with the same BCS layout as the Move type `type_`, or the unsupported part of it
+fn rust_type(type_: &str, modules: &BTreeMap<&str, &str>) -> Result<String, String> {
+    let type_ = type_.trim();
+
+    if let Some(inner) = type_.strip_prefix("vector<").and_then(|t| t.strip_suffix('>')) {
+        return Ok(format!("Vec<{}>", rust_type(inner, modules)?));
+    }
+    if let Some(inner) = type_.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        return Ok(format!("Option<{}>", rust_type(inner, modules)?));
+    }
+    if type_.starts_with("Table<") {
+        return Ok("super::Table".to_string());
+    }
+
+    Ok(match type_ {
+        "bool" | "u8" | "u16" | "u32" | "u64" | "u128" => type_.to_string(),
+        "u256" => "move_types::U256".to_string(),
+        "address" => "move_types::Address".to_string(),
+        // UID is a struct wrapping an ID, which BCS encodes as the bare 32 bytes
+        "ID" | "UID" => "move_types::ObjectId".to_string(),
+        "String" => "String".to_string(),
+        name => match modules.get(name) {
+            Some(module) => format!("super::{module}::{name}"),
+            None => return Err(name.to_string()),
+        },
+    })
+}
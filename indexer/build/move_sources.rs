@@ -0,0 +1,258 @@
+// Struct declarations read from the SuiMeet Move sources
+//
+// Shared by `build.rs`, which generates the `sealmeet` bindings from them, and by the
+// layout tests. Sources are tokenized with comments and string literals dropped, and only
+// struct declarations are parsed: `module a::b;` (or `module a::b {`) headers and
+// `[public] struct Name[<...>] [has ...] { field: Type, ... }`, or the positional
+// `(Type, ...)` form. Function bodies are skipped as tokens.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A struct declared in a Move source file
+#[derive(Debug, Clone)]
+pub struct MoveStruct {
+    pub module: String,
+    pub name: String,
+    pub abilities: Vec<String>,
+    /// (field name, field type) in declaration order, types without module paths
+    pub fields: Vec<(String, String)>,
+}
+
+impl MoveStruct {
+    #[allow(dead_code)] // only the layout tests classify structs by ability
+    pub fn has(&self, ability: &str) -> bool {
+        self.abilities.iter().any(|a| a == ability)
+    }
+}
+
+/// Every struct in the `.move` files of `sources`, sorted by module and name
+pub fn read_move_structs(sources: &Path) -> io::Result<Vec<MoveStruct>> {
+    let mut structs = Vec::new();
+
+    for entry in fs::read_dir(sources)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "move") {
+            let module = parse_module(&fs::read_to_string(&path)?)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {e}", path.display())))?;
+            structs.extend(module);
+        }
+    }
+
+    structs.sort_by(|a, b| (&a.module, &a.name).cmp(&(&b.module, &b.name)));
+    Ok(structs)
+}
+
+/// Structs declared in one Move source file
+pub fn parse_module(source: &str) -> Result<Vec<MoveStruct>, String> {
+    let tokens = tokenize(source)?;
+    let mut structs = Vec::new();
+    let mut module = String::new();
+    let mut pos = 0;
+
+    while pos < tokens.len() {
+        match tokens[pos].as_str() {
+            // `module sealmeet::sealmeet;`: the name is the last segment of the path
+            "module" => {
+                pos += 1;
+                while pos < tokens.len() && !matches!(tokens[pos].as_str(), ";" | "{") {
+                    if tokens[pos] != "::" {
+                        module = tokens[pos].clone();
+                    }
+                    pos += 1;
+                }
+            }
+            "struct" => {
+                let (parsed, end) = parse_struct(&tokens, pos + 1, &module)?;
+                structs.push(parsed);
+                pos = end;
+            }
+            _ => pos += 1,
+        }
+    }
+
+    Ok(structs)
+}
+
+/// Parse a struct declaration starting at its name; returns it and the position after it
+fn parse_struct(tokens: &[String], mut pos: usize, module: &str) -> Result<(MoveStruct, usize), String> {
+    let name = tokens
+        .get(pos)
+        .filter(|t| is_ident(t))
+        .ok_or_else(|| format!("expected a struct name in module `{module}`"))?
+        .clone();
+    let context = |e: &str| format!("struct `{module}::{name}`: {e}");
+    pos += 1;
+
+    // Type parameters are part of the type, not of the layout
+    if tokens.get(pos).is_some_and(|t| t == "<") {
+        pos = closing(tokens, pos, "<", ">").map_err(|e| context(&e))? + 1;
+    }
+
+    let mut abilities = Vec::new();
+    pos = parse_abilities(tokens, pos, &mut abilities);
+
+    let mut fields = Vec::new();
+    match tokens.get(pos).map(String::as_str) {
+        Some("{") => {
+            let end = closing(tokens, pos, "{", "}").map_err(|e| context(&e))?;
+            for field in split_top_level(&tokens[pos + 1..end]) {
+                match field {
+                    [] => {}
+                    [field, colon, type_ @ ..] if colon == ":" && is_ident(field) && !type_.is_empty() => {
+                        fields.push((field.clone(), type_string(type_)));
+                    }
+                    _ => return Err(context(&format!("cannot parse field `{}`", field.join(" ")))),
+                }
+            }
+            pos = end + 1;
+        }
+        // Positional fields are laid out like named ones, in order
+        Some("(") => {
+            let end = closing(tokens, pos, "(", ")").map_err(|e| context(&e))?;
+            for (index, type_) in split_top_level(&tokens[pos + 1..end]).into_iter().enumerate() {
+                if !type_.is_empty() {
+                    fields.push((format!("pos{index}"), type_string(type_)));
+                }
+            }
+            pos = parse_abilities(tokens, end + 1, &mut abilities);
+            if tokens.get(pos).is_some_and(|t| t == ";") {
+                pos += 1;
+            }
+        }
+        Some(";") => pos += 1,
+        other => return Err(context(&format!("expected `{{`, `(` or `;`, found {other:?}"))),
+    }
+
+    Ok((MoveStruct { module: module.to_string(), name, abilities, fields }, pos))
+}
+
+/// `has a, b, ...` at `pos`, if present; returns the position after it
+fn parse_abilities(tokens: &[String], mut pos: usize, abilities: &mut Vec<String>) -> usize {
+    if tokens.get(pos).is_none_or(|t| t != "has") {
+        return pos;
+    }
+
+    pos += 1;
+    while let Some(token) = tokens.get(pos) {
+        if is_ident(token) {
+            abilities.push(token.clone());
+        } else if token != "," {
+            break;
+        }
+        pos += 1;
+    }
+    pos
+}
+
+/// Position of the token closing the `open` at `pos`
+fn closing(tokens: &[String], pos: usize, open: &str, close: &str) -> Result<usize, String> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(pos) {
+        if token == open {
+            depth += 1;
+        } else if token == close {
+            depth -= 1;
+            if depth == 0 {
+                return Ok(i);
+            }
+        }
+    }
+    Err(format!("unbalanced `{open}`"))
+}
+
+/// Split on the commas that are not inside `<...>` or `(...)`
+fn split_top_level(tokens: &[String]) -> Vec<&[String]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.as_str() {
+            "<" | "(" => depth += 1,
+            ">" | ")" => depth -= 1,
+            "," if depth == 0 => {
+                parts.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts
+}
+
+/// A type's tokens as text without module paths, so `vector<object::ID>` becomes `vector<ID>`
+fn type_string(tokens: &[String]) -> String {
+    let mut out = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let is_path_segment = tokens.get(i + 1).is_some_and(|next| next == "::");
+        match token.as_str() {
+            "::" => {}
+            _ if is_path_segment => {}
+            "," => out.push_str(", "),
+            _ => out.push_str(token),
+        }
+    }
+    out
+}
+
+fn is_ident(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+}
+
+/// Identifiers, numbers, `::` and single punctuation characters. Comments, including nested
+/// block comments, and the contents of string literals (`b"..."`, `x"..."`) are dropped.
+fn tokenize(source: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut depth = 1;
+                while depth > 0 {
+                    match chars.next() {
+                        Some('/') if chars.next_if_eq(&'*').is_some() => depth += 1,
+                        Some('*') if chars.next_if_eq(&'/').is_some() => depth -= 1,
+                        Some(_) => {}
+                        None => return Err("unterminated block comment".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                loop {
+                    match chars.next() {
+                        Some('\\') => {
+                            chars.next();
+                        }
+                        Some('"') => break,
+                        Some(_) => {}
+                        None => return Err("unterminated string literal".to_string()),
+                    }
+                }
+                tokens.push("\"\"".to_string());
+            }
+            ':' if chars.next_if_eq(&':').is_some() => tokens.push("::".to_string()),
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut token = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    token.push(c);
+                }
+                // `b"..."` and `x"..."` are one literal
+                if (token == "b" || token == "x") && chars.peek() == Some(&'"') {
+                    continue;
+                }
+                tokens.push(token);
+            }
+            c => tokens.push(c.to_string()),
+        }
+    }
+
+    Ok(tokens)
+}
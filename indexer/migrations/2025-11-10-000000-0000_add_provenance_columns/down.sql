@@ -0,0 +1,22 @@
+-- Drop provenance columns
+DROP INDEX IF EXISTS idx_room_metadata_checkpoint;
+DROP INDEX IF EXISTS idx_room_metadata_tx_digest;
+DROP INDEX IF EXISTS idx_room_participants_checkpoint;
+DROP INDEX IF EXISTS idx_room_participants_tx_digest;
+DROP INDEX IF EXISTS idx_meeting_rooms_tx_digest;
+
+ALTER TABLE room_metadata
+    DROP COLUMN object_version,
+    DROP COLUMN transaction_digest,
+    DROP COLUMN checkpoint_timestamp_ms,
+    DROP COLUMN checkpoint_sequence_number;
+
+ALTER TABLE room_participants
+    DROP COLUMN object_version,
+    DROP COLUMN transaction_digest,
+    DROP COLUMN checkpoint_timestamp_ms,
+    DROP COLUMN checkpoint_sequence_number;
+
+ALTER TABLE meeting_rooms
+    DROP COLUMN object_version,
+    DROP COLUMN checkpoint_timestamp_ms;
@@ -0,0 +1,25 @@
+-- Checkpoint / transaction provenance for every indexed row
+-- Records which checkpoint, transaction and object version produced the current state
+
+ALTER TABLE meeting_rooms
+    ADD COLUMN checkpoint_timestamp_ms BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN object_version BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE room_participants
+    ADD COLUMN checkpoint_sequence_number BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN checkpoint_timestamp_ms BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN transaction_digest VARCHAR(64) NOT NULL DEFAULT '',
+    ADD COLUMN object_version BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE room_metadata
+    ADD COLUMN checkpoint_sequence_number BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN checkpoint_timestamp_ms BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN transaction_digest VARCHAR(64) NOT NULL DEFAULT '',
+    ADD COLUMN object_version BIGINT NOT NULL DEFAULT 0;
+
+-- Indexes for looking up rows by the transaction that produced them
+CREATE INDEX idx_meeting_rooms_tx_digest ON meeting_rooms(transaction_digest);
+CREATE INDEX idx_room_participants_tx_digest ON room_participants(transaction_digest);
+CREATE INDEX idx_room_participants_checkpoint ON room_participants(checkpoint_sequence_number);
+CREATE INDEX idx_room_metadata_tx_digest ON room_metadata(transaction_digest);
+CREATE INDEX idx_room_metadata_checkpoint ON room_metadata(checkpoint_sequence_number);
@@ -0,0 +1,2 @@
+-- Drop room_events table
+DROP TABLE IF EXISTS room_events CASCADE;
@@ -0,0 +1,23 @@
+-- Room Events Table
+-- Append-only log of every SuiMeet event with its full transaction context
+
+CREATE TABLE room_events (
+    id BIGSERIAL PRIMARY KEY,
+    checkpoint_sequence_number BIGINT NOT NULL,
+    checkpoint_timestamp_ms BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    event_index BIGINT NOT NULL, -- Position of the event within its transaction
+    event_type VARCHAR(64) NOT NULL,
+    room_id VARCHAR(66) NOT NULL, -- No FK: events outlive deleted rooms
+    sender VARCHAR(66) NOT NULL,
+    package_id VARCHAR(66) NOT NULL,
+    data JSONB NOT NULL, -- Decoded event fields
+    indexed_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE(transaction_digest, event_index)
+);
+
+-- Indexes for efficient queries
+CREATE INDEX idx_room_events_room ON room_events(room_id, checkpoint_sequence_number DESC);
+CREATE INDEX idx_room_events_type ON room_events(event_type);
+CREATE INDEX idx_room_events_sender ON room_events(sender);
+CREATE INDEX idx_room_events_checkpoint ON room_events(checkpoint_sequence_number);
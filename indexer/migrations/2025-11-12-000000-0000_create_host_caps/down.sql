@@ -0,0 +1,2 @@
+-- Drop host_caps table
+DROP TABLE IF EXISTS host_caps CASCADE;
@@ -0,0 +1,22 @@
+-- Host Caps Table
+-- Tracks every HostCap object and its current owner
+
+CREATE TABLE host_caps (
+    id BIGSERIAL PRIMARY KEY,
+    cap_id VARCHAR(66) NOT NULL UNIQUE, -- HostCap object ID
+    room_id VARCHAR(66) NOT NULL, -- No FK: caps outlive deleted rooms
+    granted_at BIGINT NOT NULL,
+    owner_kind VARCHAR(20) NOT NULL CHECK (owner_kind IN ('ADDRESS', 'OBJECT', 'SHARED', 'IMMUTABLE')),
+    owner_address VARCHAR(66), -- Owning address, or parent object ID for OBJECT
+    checkpoint_sequence_number BIGINT NOT NULL,
+    checkpoint_timestamp_ms BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    object_version BIGINT NOT NULL,
+    indexed_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Indexes for efficient queries
+CREATE INDEX idx_host_caps_room ON host_caps(room_id);
+CREATE INDEX idx_host_caps_owner ON host_caps(owner_address) WHERE owner_address IS NOT NULL;
+CREATE INDEX idx_host_caps_room_owner ON host_caps(room_id, owner_address);
@@ -0,0 +1,14 @@
+-- Drop host_cap_history table and delegated host support
+ALTER TABLE meeting_rooms DROP COLUMN participants;
+
+DELETE FROM room_participants WHERE role = 'DELEGATED_HOST';
+ALTER TABLE room_participants DROP CONSTRAINT room_participants_role_check;
+ALTER TABLE room_participants ADD CONSTRAINT room_participants_role_check
+    CHECK (role IN ('PARTICIPANT', 'HOST'));
+
+DELETE FROM host_caps WHERE owner_kind = 'WRAPPED';
+ALTER TABLE host_caps DROP CONSTRAINT host_caps_owner_kind_check;
+ALTER TABLE host_caps ADD CONSTRAINT host_caps_owner_kind_check
+    CHECK (owner_kind IN ('ADDRESS', 'OBJECT', 'SHARED', 'IMMUTABLE'));
+
+DROP TABLE IF EXISTS host_cap_history CASCADE;
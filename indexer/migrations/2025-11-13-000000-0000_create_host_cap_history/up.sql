@@ -0,0 +1,38 @@
+-- HostCap History Table
+-- Append-only log of HostCap grants, transfers, wraps and deletions
+
+CREATE TABLE host_cap_history (
+    id BIGSERIAL PRIMARY KEY,
+    cap_id VARCHAR(66) NOT NULL,
+    room_id VARCHAR(66) NOT NULL,
+    change_kind VARCHAR(20) NOT NULL CHECK (change_kind IN ('GRANTED', 'TRANSFERRED', 'WRAPPED', 'UNWRAPPED', 'DELETED')),
+    previous_owner_kind VARCHAR(20),
+    previous_owner_address VARCHAR(66),
+    owner_kind VARCHAR(20),
+    owner_address VARCHAR(66),
+    checkpoint_sequence_number BIGINT NOT NULL,
+    checkpoint_timestamp_ms BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    object_version BIGINT NOT NULL,
+    indexed_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE(cap_id, object_version)
+);
+
+-- Indexes for efficient queries
+CREATE INDEX idx_host_cap_history_cap ON host_cap_history(cap_id, checkpoint_sequence_number);
+CREATE INDEX idx_host_cap_history_room ON host_cap_history(room_id, checkpoint_sequence_number);
+CREATE INDEX idx_host_cap_history_owner ON host_cap_history(owner_address) WHERE owner_address IS NOT NULL;
+
+-- Wrapped caps stay in host_caps (they can be unwrapped later) but grant no authority
+ALTER TABLE host_caps DROP CONSTRAINT host_caps_owner_kind_check;
+ALTER TABLE host_caps ADD CONSTRAINT host_caps_owner_kind_check
+    CHECK (owner_kind IN ('ADDRESS', 'OBJECT', 'SHARED', 'IMMUTABLE', 'WRAPPED'));
+
+-- Addresses holding a HostCap without being in MeetingRoom.hosts
+ALTER TABLE room_participants DROP CONSTRAINT room_participants_role_check;
+ALTER TABLE room_participants ADD CONSTRAINT room_participants_role_check
+    CHECK (role IN ('PARTICIPANT', 'HOST', 'DELEGATED_HOST'));
+
+-- On-chain participants, needed to tell whether a demoted delegated host stays in the room
+ALTER TABLE meeting_rooms
+    ADD COLUMN participants VARCHAR(66)[] NOT NULL DEFAULT ARRAY[]::VARCHAR[];
@@ -0,0 +1,2 @@
+-- Drop room_metadata_history table
+DROP TABLE IF EXISTS room_metadata_history CASCADE;
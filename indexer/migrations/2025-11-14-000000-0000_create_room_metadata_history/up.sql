@@ -0,0 +1,21 @@
+-- Room Metadata History Table
+-- Every version of a room's MeetingMetadata dynamic field, so replaced recordings stay discoverable
+
+CREATE TABLE room_metadata_history (
+    id BIGSERIAL PRIMARY KEY,
+    room_id VARCHAR(66) NOT NULL, -- No FK: history outlives deleted rooms
+    dynamic_field_id VARCHAR(66) NOT NULL,
+    df_version BIGINT NOT NULL,
+    language TEXT NOT NULL,
+    timezone TEXT NOT NULL,
+    recording_blob_id NUMERIC(78, 0), -- u256 stored as NUMERIC, nullable
+    checkpoint_sequence_number BIGINT NOT NULL,
+    checkpoint_timestamp_ms BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    indexed_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE(dynamic_field_id, df_version)
+);
+
+-- Indexes for efficient queries
+CREATE INDEX idx_room_metadata_history_room ON room_metadata_history(room_id, checkpoint_sequence_number);
+CREATE INDEX idx_room_metadata_history_recording ON room_metadata_history(recording_blob_id) WHERE recording_blob_id IS NOT NULL;
@@ -0,0 +1,2 @@
+-- Drop room_whitelist table
+DROP TABLE IF EXISTS room_whitelist CASCADE;
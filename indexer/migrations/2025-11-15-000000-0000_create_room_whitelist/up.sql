@@ -0,0 +1,24 @@
+-- Room Whitelist Table
+-- The Seal whitelist wrapped in each MeetingRoom, which is what actually gates decryption
+
+CREATE TABLE room_whitelist (
+    id BIGSERIAL PRIMARY KEY,
+    room_id VARCHAR(66) NOT NULL UNIQUE,
+    seal_policy_id VARCHAR(66) NOT NULL,
+    whitelist VARCHAR(66)[] NOT NULL DEFAULT ARRAY[]::VARCHAR[],
+    whitelist_updated_at BIGINT NOT NULL, -- Epoch at creation, clock ms after later changes
+    missing_from_whitelist VARCHAR(66)[] NOT NULL DEFAULT ARRAY[]::VARCHAR[], -- Participants not whitelisted
+    extra_in_whitelist VARCHAR(66)[] NOT NULL DEFAULT ARRAY[]::VARCHAR[], -- Whitelisted non-participants
+    has_drift BOOLEAN NOT NULL DEFAULT false,
+    checkpoint_sequence_number BIGINT NOT NULL,
+    checkpoint_timestamp_ms BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    object_version BIGINT NOT NULL,
+    indexed_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (room_id) REFERENCES meeting_rooms(room_id) ON DELETE CASCADE
+);
+
+-- Indexes for efficient queries
+CREATE INDEX idx_room_whitelist_addresses ON room_whitelist USING GIN(whitelist);
+CREATE INDEX idx_room_whitelist_drift ON room_whitelist(room_id) WHERE has_drift;
@@ -0,0 +1,6 @@
+-- Drop room registry tables
+DROP VIEW IF EXISTS meeting_rooms_registry;
+DROP TABLE IF EXISTS registry_seal_rooms CASCADE;
+DROP TABLE IF EXISTS registry_host_rooms CASCADE;
+DROP TABLE IF EXISTS room_registry_snapshots CASCADE;
+DROP TABLE IF EXISTS room_registry CASCADE;
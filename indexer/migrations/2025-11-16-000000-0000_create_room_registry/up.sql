@@ -0,0 +1,94 @@
+-- Room Registry Tables
+-- The shared RoomRegistry object, its snapshots, and the entries of its two Tables
+
+CREATE TABLE room_registry (
+    id BIGSERIAL PRIMARY KEY,
+    registry_id VARCHAR(66) NOT NULL UNIQUE,
+    room_count BIGINT NOT NULL,
+    all_rooms VARCHAR(66)[] NOT NULL DEFAULT ARRAY[]::VARCHAR[],
+    active_rooms VARCHAR(66)[] NOT NULL DEFAULT ARRAY[]::VARCHAR[],
+    scheduled_rooms VARCHAR(66)[] NOT NULL DEFAULT ARRAY[]::VARCHAR[],
+    rooms_by_host_table_id VARCHAR(66) NOT NULL,
+    rooms_by_seal_id_table_id VARCHAR(66) NOT NULL,
+    checkpoint_sequence_number BIGINT NOT NULL,
+    checkpoint_timestamp_ms BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    object_version BIGINT NOT NULL,
+    indexed_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- One row per registry version seen at a checkpoint boundary
+CREATE TABLE room_registry_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    registry_id VARCHAR(66) NOT NULL,
+    room_count BIGINT NOT NULL,
+    all_rooms VARCHAR(66)[] NOT NULL,
+    active_rooms VARCHAR(66)[] NOT NULL,
+    scheduled_rooms VARCHAR(66)[] NOT NULL,
+    checkpoint_sequence_number BIGINT NOT NULL,
+    checkpoint_timestamp_ms BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    object_version BIGINT NOT NULL,
+    indexed_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE(registry_id, object_version)
+);
+
+-- rooms_by_host: Table<address, vector<ID>>
+CREATE TABLE registry_host_rooms (
+    id BIGSERIAL PRIMARY KEY,
+    dynamic_field_id VARCHAR(66) NOT NULL UNIQUE,
+    table_id VARCHAR(66) NOT NULL,
+    host_address VARCHAR(66) NOT NULL,
+    room_ids VARCHAR(66)[] NOT NULL DEFAULT ARRAY[]::VARCHAR[],
+    checkpoint_sequence_number BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- rooms_by_seal_id: Table<ID, ID>
+CREATE TABLE registry_seal_rooms (
+    id BIGSERIAL PRIMARY KEY,
+    dynamic_field_id VARCHAR(66) NOT NULL UNIQUE,
+    table_id VARCHAR(66) NOT NULL,
+    seal_policy_id VARCHAR(66) NOT NULL,
+    room_id VARCHAR(66) NOT NULL,
+    checkpoint_sequence_number BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Indexes for efficient queries
+CREATE INDEX idx_room_registry_snapshots_checkpoint ON room_registry_snapshots(registry_id, checkpoint_sequence_number DESC);
+CREATE INDEX idx_registry_host_rooms_host ON registry_host_rooms(host_address);
+CREATE INDEX idx_registry_seal_rooms_seal ON registry_seal_rooms(seal_policy_id);
+CREATE INDEX idx_registry_seal_rooms_room ON registry_seal_rooms(room_id);
+
+-- One row per indexed room, UNLISTED until a registry is indexed. The registry processor only
+-- indexes the RoomRegistry type of the configured package, so the most recently indexed
+-- registry is that package's; registries left by an earlier --suimeet-package-id are ignored.
+CREATE VIEW meeting_rooms_registry AS
+SELECT
+    mr.room_id,
+    mr.title,
+    mr.status,
+    rr.registry_id,
+    CASE
+        WHEN mr.room_id = ANY(rr.active_rooms) THEN 'ACTIVE'
+        WHEN mr.room_id = ANY(rr.scheduled_rooms) THEN 'SCHEDULED'
+        WHEN mr.room_id = ANY(rr.all_rooms) THEN 'LISTED'
+        ELSE 'UNLISTED'
+    END AS registry_status,
+    -- Registry and room status should agree: 1 = scheduled, 2 = active, 3 = ended
+    CASE
+        WHEN mr.status = 1 THEN coalesce(mr.room_id = ANY(rr.scheduled_rooms), FALSE)
+        WHEN mr.status = 2 THEN coalesce(mr.room_id = ANY(rr.active_rooms), FALSE)
+        ELSE NOT coalesce(mr.room_id = ANY(rr.active_rooms) OR mr.room_id = ANY(rr.scheduled_rooms), FALSE)
+    END AS registry_consistent
+FROM meeting_rooms mr
+LEFT JOIN LATERAL (
+    SELECT registry_id, all_rooms, active_rooms, scheduled_rooms
+    FROM room_registry
+    ORDER BY checkpoint_sequence_number DESC, id DESC
+    LIMIT 1
+) rr ON TRUE;
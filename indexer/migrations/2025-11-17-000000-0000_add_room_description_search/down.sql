@@ -0,0 +1,7 @@
+-- Drop room description and search columns
+DROP INDEX IF EXISTS idx_meeting_rooms_description_trgm;
+DROP INDEX IF EXISTS idx_meeting_rooms_title_trgm;
+DROP INDEX IF EXISTS idx_meeting_rooms_search;
+
+ALTER TABLE meeting_rooms DROP COLUMN search_vector;
+ALTER TABLE meeting_rooms DROP COLUMN description;
@@ -0,0 +1,16 @@
+-- Room description and full-text / trigram search over title and description
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE meeting_rooms ADD COLUMN description TEXT;
+
+-- 'simple' config: room titles are short, multilingual and often not dictionary words
+ALTER TABLE meeting_rooms ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(title, '')), 'A') ||
+        setweight(to_tsvector('simple', coalesce(description, '')), 'B')
+    ) STORED;
+
+CREATE INDEX idx_meeting_rooms_search ON meeting_rooms USING GIN(search_vector);
+CREATE INDEX idx_meeting_rooms_title_trgm ON meeting_rooms USING GIN(title gin_trgm_ops);
+CREATE INDEX idx_meeting_rooms_description_trgm ON meeting_rooms USING GIN(description gin_trgm_ops);
@@ -0,0 +1,10 @@
+-- Drop participant status columns (revoked participants are removed)
+DROP INDEX IF EXISTS idx_room_participants_status;
+
+DELETE FROM room_participants WHERE status = 'REVOKED';
+
+ALTER TABLE room_participants
+    DROP COLUMN revoked_by,
+    DROP COLUMN approved_by,
+    DROP COLUMN left_at,
+    DROP COLUMN status;
@@ -0,0 +1,15 @@
+-- Participant status and on-chain join / leave times
+-- Revoked participants are kept (status = 'REVOKED') instead of deleted, for attendance reports
+
+ALTER TABLE room_participants
+    ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'ACTIVE' CHECK (status IN ('ACTIVE', 'REVOKED')),
+    ADD COLUMN left_at TIMESTAMP, -- Checkpoint time of the revoking transaction
+    ADD COLUMN approved_by VARCHAR(66), -- From GuestApproved
+    ADD COLUMN revoked_by VARCHAR(66); -- From GuestRevoked
+
+-- joined_at is now the checkpoint time of the transaction that added the participant
+UPDATE room_participants
+SET joined_at = to_timestamp(checkpoint_timestamp_ms / 1000.0) AT TIME ZONE 'UTC'
+WHERE checkpoint_timestamp_ms > 0;
+
+CREATE INDEX idx_room_participants_status ON room_participants(room_id, status);
@@ -0,0 +1,6 @@
+-- Drop package version columns
+ALTER TABLE room_events DROP COLUMN package_version;
+ALTER TABLE host_caps DROP COLUMN package_version;
+ALTER TABLE room_metadata DROP COLUMN package_version;
+ALTER TABLE room_participants DROP COLUMN package_version;
+ALTER TABLE meeting_rooms DROP COLUMN package_version;
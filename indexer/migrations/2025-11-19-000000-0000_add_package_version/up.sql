@@ -0,0 +1,16 @@
+-- SuiMeet package version that produced each row
+-- 1 is the original publish, each upgrade listed in --suimeet-upgraded-package-ids adds one.
+-- NULL when the producing transaction did not call SuiMeet code directly.
+
+ALTER TABLE meeting_rooms ADD COLUMN package_version BIGINT;
+ALTER TABLE room_participants ADD COLUMN package_version BIGINT;
+ALTER TABLE room_metadata ADD COLUMN package_version BIGINT;
+ALTER TABLE host_caps ADD COLUMN package_version BIGINT;
+ALTER TABLE room_events ADD COLUMN package_version BIGINT;
+
+-- Everything indexed so far came from a single package id
+UPDATE meeting_rooms SET package_version = 1;
+UPDATE room_participants SET package_version = 1;
+UPDATE room_metadata SET package_version = 1;
+UPDATE host_caps SET package_version = 1;
+UPDATE room_events SET package_version = 1;
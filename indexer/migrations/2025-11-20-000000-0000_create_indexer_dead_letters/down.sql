@@ -0,0 +1,2 @@
+-- Drop indexer_dead_letters table
+DROP TABLE IF EXISTS indexer_dead_letters;
@@ -0,0 +1,36 @@
+-- Objects and events that failed to decode, kept with their raw BCS bytes for retry
+CREATE TABLE indexer_dead_letters (
+    id BIGSERIAL PRIMARY KEY,
+    pipeline VARCHAR(64) NOT NULL,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('OBJECT', 'EVENT')),
+    item_key VARCHAR(140) NOT NULL, -- Object ID, or "<transaction digest>:<event index>"
+    type_tag TEXT NOT NULL,
+    object_id VARCHAR(66),
+    event_index BIGINT,
+    -- Where a failed object was in its checkpoint: LIVE for its final state, WRAPPED or DELETED
+    -- for an input object that did not survive the checkpoint. Set for every object, never for events
+    object_state VARCHAR(10) CHECK (object_state IN ('LIVE', 'WRAPPED', 'DELETED')),
+    raw_bytes BYTEA NOT NULL, -- BCS of the whole Object / Event
+    error TEXT NOT NULL, -- Latest decode error
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'RESOLVED', 'SUPERSEDED')),
+    retry_count INTEGER NOT NULL DEFAULT 0,
+    last_retry_at TIMESTAMP,
+    resolved_at TIMESTAMP,
+
+    -- Provenance of the failed item
+    checkpoint_sequence_number BIGINT NOT NULL,
+    checkpoint_timestamp_ms BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    object_version BIGINT NOT NULL,
+    package_version BIGINT,
+
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+
+    CHECK ((kind = 'OBJECT') = (object_state IS NOT NULL)),
+
+    -- A replayed checkpoint records the same failure only once
+    UNIQUE (pipeline, checkpoint_sequence_number, item_key)
+);
+
+CREATE INDEX idx_indexer_dead_letters_pending ON indexer_dead_letters(pipeline, checkpoint_sequence_number)
+    WHERE status = 'PENDING';
@@ -0,0 +1,6 @@
+-- Hand metadata dead letters back to a separate metadata pipeline. Its watermark is
+-- recreated from the first checkpoint it is started with.
+UPDATE indexer_dead_letters
+SET pipeline = 'metadata_processor'
+WHERE pipeline = 'room_processor'
+  AND type_tag LIKE '%::dynamic_field::Field<%';
@@ -0,0 +1,27 @@
+-- Room metadata is now committed by the room pipeline (room_processor), in the same
+-- transaction as the rooms its foreign key points to
+
+-- Pending metadata failures are retried by the room pipeline from now on
+UPDATE indexer_dead_letters
+SET pipeline = 'room_processor'
+WHERE pipeline = 'metadata_processor';
+
+-- If the old metadata pipeline lagged behind the room pipeline, rewind the room pipeline
+-- to it so the metadata of those checkpoints is not skipped. Replaying room checkpoints
+-- is idempotent. Then drop the old pipeline's watermark.
+DO $$
+BEGIN
+    IF to_regclass('watermarks') IS NOT NULL THEN
+        UPDATE watermarks r
+        SET epoch_hi_inclusive = m.epoch_hi_inclusive,
+            checkpoint_hi_inclusive = m.checkpoint_hi_inclusive,
+            tx_hi = m.tx_hi,
+            timestamp_ms_hi_inclusive = m.timestamp_ms_hi_inclusive
+        FROM watermarks m
+        WHERE r.pipeline = 'room_processor'
+          AND m.pipeline = 'metadata_processor'
+          AND m.checkpoint_hi_inclusive < r.checkpoint_hi_inclusive;
+
+        DELETE FROM watermarks WHERE pipeline = 'metadata_processor';
+    END IF;
+END $$;
@@ -0,0 +1,20 @@
+-- Committed changes, appended by the pipelines in their commit transaction and streamed
+-- to live subscribers by the read API
+CREATE TABLE room_changes (
+    id BIGSERIAL PRIMARY KEY, -- Appended under an advisory lock, so ids commit in order
+    room_id VARCHAR(66) NOT NULL,
+    change_kind VARCHAR(32) NOT NULL,
+    addresses VARCHAR(66)[] NOT NULL DEFAULT '{}', -- Addresses the change concerns
+    data JSONB NOT NULL,
+
+    -- Provenance of the change
+    checkpoint_sequence_number BIGINT NOT NULL,
+    checkpoint_timestamp_ms BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_room_changes_room_id ON room_changes(room_id, id);
+CREATE INDEX idx_room_changes_addresses ON room_changes USING GIN(addresses);
+CREATE INDEX idx_room_changes_created_at ON room_changes(created_at);
@@ -0,0 +1,44 @@
+-- Webhook endpoints, each subscribed to the lifecycle events of one host's rooms or of one room
+CREATE TABLE webhook_subscriptions (
+    id BIGSERIAL PRIMARY KEY,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL, -- HMAC-SHA256 key signing each delivery
+    host_address VARCHAR(66), -- Rooms this address created or was granted a HostCap of
+    room_id VARCHAR(66),
+    event_types VARCHAR(64)[] NOT NULL DEFAULT '{}', -- Empty for every lifecycle event
+    active BOOLEAN NOT NULL DEFAULT TRUE, -- Inactive subscriptions keep their deliveries pending
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), -- Compared with checkpoint timestamps, so zoned
+    CHECK ((host_address IS NULL) <> (room_id IS NULL))
+);
+
+CREATE INDEX idx_webhook_subscriptions_host ON webhook_subscriptions(host_address) WHERE host_address IS NOT NULL;
+CREATE INDEX idx_webhook_subscriptions_room ON webhook_subscriptions(room_id) WHERE room_id IS NOT NULL;
+
+-- Outbox of deliveries, written by the event pipeline in its commit transaction and drained
+-- by the --deliver-webhooks worker
+CREATE TABLE webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    subscription_id BIGINT NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    event_type VARCHAR(64) NOT NULL,
+    room_id VARCHAR(66) NOT NULL,
+    data JSONB NOT NULL, -- Decoded event fields
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'DELIVERED', 'FAILED')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    last_status_code INTEGER,
+    last_error TEXT,
+    delivered_at TIMESTAMP,
+
+    -- Provenance of the event
+    checkpoint_sequence_number BIGINT NOT NULL,
+    checkpoint_timestamp_ms BIGINT NOT NULL,
+    transaction_digest VARCHAR(64) NOT NULL,
+    event_index BIGINT NOT NULL,
+
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+
+    -- An event is delivered to a subscription once
+    UNIQUE (subscription_id, transaction_digest, event_index)
+);
+
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries(next_attempt_at, id) WHERE status = 'PENDING';
@@ -0,0 +1,359 @@
+// GraphQL schema over the `db::models` read models
+//
+// The models derive `SimpleObject` for their columns; the relationships below resolve through
+// `db::queries`, the same queries the REST routes use. List fields return the keyset `Page`
+// of those queries as `RoomPage`, `ParticipantPage` or `EventPage`: pass `nextCursor` back as
+// `after`. A room, its metadata, participants or events reached from every item of a list are
+// loaded through a `DataLoader`, one query for the whole list.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::dataloader::{DataLoader, Loader};
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, EmptySubscription, Json, Object, OutputType, Result, Schema, SimpleObject,
+};
+use axum::response::{Html, IntoResponse};
+use sui_indexer_alt_framework::postgres::Db;
+
+use super::page_size;
+use crate::db::models::{MeetingRoom, RoomEvent, RoomMetadata, RoomParticipant};
+use crate::db::queries::{self, Page, RoomFilter};
+
+/// Nesting allowed in one query, e.g. rooms → participants → rooms → metadata
+const MAX_DEPTH: usize = 8;
+/// Complexity allowed in one query. A list field costs its page size times the fields
+/// selected on each item, so nested lists multiply
+const MAX_COMPLEXITY: usize = 1000;
+
+pub type RoomSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// The GraphQL schema, reading from `db`
+pub fn schema(db: Db) -> RoomSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(DataLoader::new(RoomLoader(db.clone()), tokio::spawn))
+        .data(DataLoader::new(MetadataLoader(db.clone()), tokio::spawn))
+        .data(DataLoader::new(ParticipantsLoader(db.clone()), tokio::spawn))
+        .data(DataLoader::new(EventsLoader(db.clone()), tokio::spawn))
+        .data(db)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// GraphiQL explorer for the `/graphql` endpoint
+pub async fn graphiql() -> impl IntoResponse {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+/// Rooms by ID, batched across the resolvers of one query
+pub struct RoomLoader(Db);
+
+impl Loader<String> for RoomLoader {
+    type Value = MeetingRoom;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, room_ids: &[String]) -> Result<HashMap<String, MeetingRoom>, Self::Error> {
+        let mut conn = self.0.connect().await.map_err(Arc::new)?;
+        let rooms = queries::rooms_by_ids(&mut conn, room_ids).await.map_err(Arc::new)?;
+
+        Ok(rooms.into_iter().map(|room| (room.room_id.clone(), room)).collect())
+    }
+}
+
+/// Current room metadata by room ID, batched across the resolvers of one query
+pub struct MetadataLoader(Db);
+
+impl Loader<String> for MetadataLoader {
+    type Value = RoomMetadata;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, room_ids: &[String]) -> Result<HashMap<String, RoomMetadata>, Self::Error> {
+        let mut conn = self.0.connect().await.map_err(Arc::new)?;
+        let metadata = queries::rooms_metadata(&mut conn, room_ids).await.map_err(Arc::new)?;
+
+        Ok(metadata.into_iter().map(|metadata| (metadata.room_id.clone(), metadata)).collect())
+    }
+}
+
+/// A page of participants of one room, as the `participants` field asks for it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParticipantsKey {
+    room_id: String,
+    after: Option<i64>,
+    limit: i64,
+}
+
+/// Participant pages by room, batched across the resolvers of one query
+pub struct ParticipantsLoader(Db);
+
+impl Loader<ParticipantsKey> for ParticipantsLoader {
+    type Value = Page<RoomParticipant>;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, keys: &[ParticipantsKey]) -> Result<HashMap<ParticipantsKey, Self::Value>, Self::Error> {
+        // The rooms of one list share the field's arguments, so this is one query per list
+        let mut rooms_by_args: HashMap<(Option<i64>, i64), Vec<String>> = HashMap::new();
+        for key in keys {
+            rooms_by_args.entry((key.after, key.limit)).or_default().push(key.room_id.clone());
+        }
+
+        let mut conn = self.0.connect().await.map_err(Arc::new)?;
+        let mut pages = HashMap::new();
+        for ((after, limit), room_ids) in rooms_by_args {
+            let by_room = queries::rooms_participants(&mut conn, &room_ids, after, limit)
+                .await
+                .map_err(Arc::new)?;
+            pages.extend(by_room.into_iter().map(|(room_id, page)| (ParticipantsKey { room_id, after, limit }, page)));
+        }
+
+        Ok(pages)
+    }
+}
+
+/// A page of events of one room, as the `events` field asks for it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventsKey {
+    room_id: String,
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    after: Option<i64>,
+    limit: i64,
+}
+
+/// Event pages by room, batched across the resolvers of one query
+pub struct EventsLoader(Db);
+
+impl Loader<EventsKey> for EventsLoader {
+    type Value = Page<RoomEvent>;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, keys: &[EventsKey]) -> Result<HashMap<EventsKey, Self::Value>, Self::Error> {
+        // The rooms of one list share the field's arguments, so this is one query per list
+        let mut rooms_by_args: HashMap<(Option<i64>, Option<i64>, Option<i64>, i64), Vec<String>> = HashMap::new();
+        for key in keys {
+            rooms_by_args
+                .entry((key.since_ms, key.until_ms, key.after, key.limit))
+                .or_default()
+                .push(key.room_id.clone());
+        }
+
+        let mut conn = self.0.connect().await.map_err(Arc::new)?;
+        let mut pages = HashMap::new();
+        for ((since_ms, until_ms, after, limit), room_ids) in rooms_by_args {
+            let by_room = queries::rooms_events(&mut conn, &room_ids, since_ms, until_ms, after, limit)
+                .await
+                .map_err(Arc::new)?;
+            pages.extend(by_room.into_iter().map(|(room_id, page)| {
+                (EventsKey { room_id, since_ms, until_ms, after, limit }, page)
+            }));
+        }
+
+        Ok(pages)
+    }
+}
+
+/// One page of a list field; pass `nextCursor` back as `after`
+#[derive(SimpleObject, Debug, Clone)]
+#[graphql(
+    concrete(name = "RoomPage", params(MeetingRoom)),
+    concrete(name = "ParticipantPage", params(RoomParticipant)),
+    concrete(name = "EventPage", params(RoomEvent))
+)]
+pub struct GraphqlPage<T: OutputType> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<i64>,
+}
+
+impl<T: OutputType> From<Page<T>> for GraphqlPage<T> {
+    fn from(page: Page<T>) -> Self {
+        GraphqlPage {
+            items: page.items,
+            next_cursor: page.next_cursor,
+        }
+    }
+}
+
+/// The room `room_id` through the query's `RoomLoader`
+async fn load_room(ctx: &Context<'_>, room_id: &str) -> Result<Option<MeetingRoom>> {
+    Ok(ctx.data::<DataLoader<RoomLoader>>()?.load_one(room_id.to_string()).await?)
+}
+
+/// Page for a room the loader returned nothing for
+fn empty_page<T: OutputType>() -> GraphqlPage<T> {
+    GraphqlPage {
+        items: Vec::new(),
+        next_cursor: None,
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// A room by its object ID
+    async fn room(&self, ctx: &Context<'_>, room_id: String) -> Result<Option<MeetingRoom>> {
+        load_room(ctx, &room_id).await
+    }
+
+    /// Rooms matching every given filter, most recently indexed first. `createdAfter` and
+    /// `createdBefore` bound `createdAt` (ms), the latter exclusive.
+    #[allow(clippy::too_many_arguments)]
+    #[graphql(complexity = "page_size(first) as usize * child_complexity")]
+    async fn rooms(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<i16>,
+        host: Option<String>,
+        participant: Option<String>,
+        created_after: Option<i64>,
+        created_before: Option<i64>,
+        first: Option<i64>,
+        after: Option<i64>,
+    ) -> Result<GraphqlPage<MeetingRoom>> {
+        let filter = RoomFilter {
+            status,
+            host,
+            participant,
+            created_after,
+            created_before,
+        };
+
+        let mut conn = ctx.data::<Db>()?.connect().await?;
+        Ok(queries::list_rooms(&mut conn, &filter, after, page_size(first)).await?.into())
+    }
+}
+
+#[ComplexObject]
+impl MeetingRoom {
+    /// Participant rows of this room, revoked ones included
+    #[graphql(complexity = "page_size(first) as usize * child_complexity")]
+    async fn participants(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i64>,
+        after: Option<i64>,
+    ) -> Result<GraphqlPage<RoomParticipant>> {
+        let key = ParticipantsKey {
+            room_id: self.room_id.clone(),
+            after,
+            limit: page_size(first),
+        };
+
+        let page = ctx.data::<DataLoader<ParticipantsLoader>>()?.load_one(key).await?;
+        Ok(page.map_or_else(empty_page, Into::into))
+    }
+
+    /// Current metadata of this room
+    async fn metadata(&self, ctx: &Context<'_>) -> Result<Option<RoomMetadata>> {
+        Ok(ctx.data::<DataLoader<MetadataLoader>>()?.load_one(self.room_id.clone()).await?)
+    }
+
+    /// Events of this room, newest first. `sinceMs` and `untilMs` bound the checkpoint
+    /// timestamp, the latter exclusive.
+    #[graphql(complexity = "page_size(first) as usize * child_complexity")]
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        since_ms: Option<i64>,
+        until_ms: Option<i64>,
+        first: Option<i64>,
+        after: Option<i64>,
+    ) -> Result<GraphqlPage<RoomEvent>> {
+        let key = EventsKey {
+            room_id: self.room_id.clone(),
+            since_ms,
+            until_ms,
+            after,
+            limit: page_size(first),
+        };
+
+        let page = ctx.data::<DataLoader<EventsLoader>>()?.load_one(key).await?;
+        Ok(page.map_or_else(empty_page, Into::into))
+    }
+}
+
+#[ComplexObject]
+impl RoomParticipant {
+    /// The room this row belongs to
+    async fn room(&self, ctx: &Context<'_>) -> Result<Option<MeetingRoom>> {
+        load_room(ctx, &self.room_id).await
+    }
+
+    /// Rooms this address is an active participant of, this one included
+    #[graphql(complexity = "page_size(first) as usize * child_complexity")]
+    async fn rooms(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<i16>,
+        first: Option<i64>,
+        after: Option<i64>,
+    ) -> Result<GraphqlPage<MeetingRoom>> {
+        let filter = RoomFilter {
+            status,
+            participant: Some(self.participant_address.clone()),
+            ..Default::default()
+        };
+
+        let mut conn = ctx.data::<Db>()?.connect().await?;
+        Ok(queries::list_rooms(&mut conn, &filter, after, page_size(first)).await?.into())
+    }
+}
+
+#[ComplexObject]
+impl RoomEvent {
+    /// Decoded event fields
+    async fn data(&self) -> Json<serde_json::Value> {
+        Json(self.data.clone())
+    }
+
+    /// The room the event belongs to
+    async fn room(&self, ctx: &Context<'_>) -> Result<Option<MeetingRoom>> {
+        load_room(ctx, &self.room_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_exposes_relationships() {
+        let sdl = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+            .finish()
+            .sdl();
+
+        for field in [
+            "participants(first: Int, after: Int): ParticipantPage!",
+            "participantAddresses: [String!]!",
+            "metadata: RoomMetadata",
+            "events(sinceMs: Int, untilMs: Int, first: Int, after: Int): EventPage!",
+            "rooms(status: Int, first: Int, after: Int): RoomPage!",
+            "nextCursor: Int",
+        ] {
+            assert!(sdl.contains(field), "schema is missing `{field}`:\n{sdl}");
+        }
+    }
+
+    /// Whether the complexity limit rejects `query`, which happens before anything is
+    /// resolved, so no database is needed
+    async fn too_complex(query: &str) -> bool {
+        let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+            .limit_complexity(MAX_COMPLEXITY)
+            .finish();
+
+        let errors = schema.execute(query).await.errors;
+        errors.iter().any(|e| e.message.contains("too complex"))
+    }
+
+    #[tokio::test]
+    async fn test_nested_lists_multiply_complexity() {
+        // 200 rooms of 200 participants each
+        let fan_out = "{ rooms(first: 200) { items { participants(first: 200) { items { participantAddress } } } } }";
+        assert!(too_complex(fan_out).await);
+
+        let small = "{ rooms(first: 5) { items { participants(first: 5) { items { participantAddress } } } } }";
+        assert!(!too_complex(small).await);
+    }
+}
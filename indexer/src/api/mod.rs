@@ -0,0 +1,257 @@
+// Read API - HTTP/JSON access to the indexed tables
+//
+// `--serve-api` runs it in place of indexing, so it scales separately from the pipelines.
+// Every route is a thin wrapper over `db::queries`: applications read rooms through the
+// crate that owns the schema rather than re-declaring the tables. `/openapi.json`
+// describes the routes; `/graphql` serves the same queries as a GraphQL schema, with
+// GraphiQL at `/graphiql`. `/changes` and `/changes/ws` stream committed changes live.
+
+mod graphql;
+mod stream;
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use async_graphql_axum::GraphQL;
+use axum::extract::{FromRef, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::json;
+use sui_indexer_alt_framework::postgres::Db;
+use tokio::net::TcpListener;
+use tracing::{error, info};
+use utoipa::{IntoParams, OpenApi};
+
+use crate::db::models::{MeetingRoom, RoomMetadata, RoomParticipant};
+use crate::db::queries::{self, Page, RoomFilter};
+use stream::ChangeFeed;
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "SuiMeet Indexer API", description = "Read-only access to indexed SuiMeet rooms"),
+    paths(list_rooms, get_room, list_room_participants, get_room_metadata, stream::changes_sse)
+)]
+pub struct ApiDoc;
+
+/// Shared by every route; handlers extract the part they need
+#[derive(Clone)]
+struct ApiState {
+    db: Db,
+    feed: ChangeFeed,
+}
+
+impl FromRef<ApiState> for Db {
+    fn from_ref(state: &ApiState) -> Self {
+        state.db.clone()
+    }
+}
+
+impl FromRef<ApiState> for ChangeFeed {
+    fn from_ref(state: &ApiState) -> Self {
+        state.feed.clone()
+    }
+}
+
+/// Routes of the read API over the database `db`, streaming changes from `feed`
+fn router(db: Db, feed: ChangeFeed) -> Router {
+    Router::new()
+        .route("/rooms", get(list_rooms))
+        .route("/rooms/{room_id}", get(get_room))
+        .route("/rooms/{room_id}/participants", get(list_room_participants))
+        .route("/rooms/{room_id}/metadata", get(get_room_metadata))
+        .route("/openapi.json", get(|| async { Json(ApiDoc::openapi()) }))
+        .route_service("/graphql", GraphQL::new(graphql::schema(db.clone())))
+        .route("/graphiql", get(graphql::graphiql))
+        .route("/changes", get(stream::changes_sse))
+        .route("/changes/ws", get(stream::changes_ws))
+        .with_state(ApiState { db, feed })
+}
+
+/// Serve the read API on `address` until the process is stopped, polling the change log
+/// every `change_poll_interval`
+pub async fn serve(db: Db, address: SocketAddr, change_poll_interval: Duration) -> anyhow::Result<()> {
+    let feed = ChangeFeed::start(db.clone(), change_poll_interval).await?;
+
+    let listener = TcpListener::bind(address).await?;
+    info!("Serving read API on {address}");
+
+    axum::serve(listener, router(db, feed)).await?;
+    Ok(())
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+#[into_params(parameter_in = Query)]
+struct RoomsQuery {
+    /// 1 = scheduled, 2 = active, 3 = ended
+    status: Option<i16>,
+    /// Rooms this address hosts
+    host: Option<String>,
+    /// Rooms this address is an active participant of
+    participant: Option<String>,
+    /// Rooms created at or after this time (ms)
+    created_after: Option<i64>,
+    /// Rooms created before this time (ms)
+    created_before: Option<i64>,
+    /// `next_cursor` of the previous page
+    cursor: Option<i64>,
+    /// Page size, at most 200 (default 50)
+    limit: Option<i64>,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+#[into_params(parameter_in = Query)]
+struct PageQuery {
+    /// `next_cursor` of the previous page
+    cursor: Option<i64>,
+    /// Page size, at most 200 (default 50)
+    limit: Option<i64>,
+}
+
+fn page_size(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+/// Rooms matching every given filter, most recently indexed first
+#[utoipa::path(
+    get,
+    path = "/rooms",
+    params(RoomsQuery),
+    responses((status = 200, description = "One page of rooms", body = Page<MeetingRoom>))
+)]
+async fn list_rooms(
+    State(db): State<Db>,
+    Query(query): Query<RoomsQuery>,
+) -> Result<Json<Page<MeetingRoom>>, ApiError> {
+    let filter = RoomFilter {
+        status: query.status,
+        host: query.host,
+        participant: query.participant,
+        created_after: query.created_after,
+        created_before: query.created_before,
+    };
+
+    let mut conn = db.connect().await?;
+    let page = queries::list_rooms(&mut conn, &filter, query.cursor, page_size(query.limit)).await?;
+    Ok(Json(page))
+}
+
+/// A room by its object ID
+#[utoipa::path(
+    get,
+    path = "/rooms/{room_id}",
+    params(("room_id" = String, Path, description = "MeetingRoom object ID")),
+    responses(
+        (status = 200, description = "The room", body = MeetingRoom),
+        (status = 404, description = "No such room is indexed")
+    )
+)]
+async fn get_room(
+    State(db): State<Db>,
+    Path(room_id): Path<String>,
+) -> Result<Json<MeetingRoom>, ApiError> {
+    let mut conn = db.connect().await?;
+    match queries::room_by_id(&mut conn, &room_id).await? {
+        Some(room) => Ok(Json(room)),
+        None => Err(ApiError::NotFound(format!("Room {room_id} not found"))),
+    }
+}
+
+/// Participants of a room, revoked ones included
+#[utoipa::path(
+    get,
+    path = "/rooms/{room_id}/participants",
+    params(("room_id" = String, Path, description = "MeetingRoom object ID"), PageQuery),
+    responses((status = 200, description = "One page of participants", body = Page<RoomParticipant>))
+)]
+async fn list_room_participants(
+    State(db): State<Db>,
+    Path(room_id): Path<String>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<Page<RoomParticipant>>, ApiError> {
+    let mut conn = db.connect().await?;
+    let page =
+        queries::room_participants(&mut conn, &room_id, query.cursor, page_size(query.limit)).await?;
+    Ok(Json(page))
+}
+
+/// Current metadata of a room
+#[utoipa::path(
+    get,
+    path = "/rooms/{room_id}/metadata",
+    params(("room_id" = String, Path, description = "MeetingRoom object ID")),
+    responses(
+        (status = 200, description = "The room's metadata", body = RoomMetadata),
+        (status = 404, description = "The room has no metadata indexed")
+    )
+)]
+async fn get_room_metadata(
+    State(db): State<Db>,
+    Path(room_id): Path<String>,
+) -> Result<Json<RoomMetadata>, ApiError> {
+    let mut conn = db.connect().await?;
+    match queries::room_metadata(&mut conn, &room_id).await? {
+        Some(metadata) => Ok(Json(metadata)),
+        None => Err(ApiError::NotFound(format!("No metadata for room {room_id}"))),
+    }
+}
+
+enum ApiError {
+    NotFound(String),
+    Internal(anyhow::Error),
+}
+
+impl<E: Into<anyhow::Error>> From<E> for ApiError {
+    fn from(e: E) -> Self {
+        ApiError::Internal(e.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            ApiError::Internal(e) => {
+                error!("Read API query failed: {e:#}");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal error".to_string())
+            }
+        };
+
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_describes_every_route() {
+        let doc = ApiDoc::openapi();
+        let mut paths: Vec<&str> = doc.paths.paths.keys().map(String::as_str).collect();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                "/changes",
+                "/rooms",
+                "/rooms/{room_id}",
+                "/rooms/{room_id}/metadata",
+                "/rooms/{room_id}/participants",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_page_size_is_clamped() {
+        assert_eq!(page_size(None), DEFAULT_PAGE_SIZE);
+        assert_eq!(page_size(Some(0)), 1);
+        assert_eq!(page_size(Some(10_000)), MAX_PAGE_SIZE);
+    }
+}
@@ -0,0 +1,245 @@
+// Live change stream - the `room_changes` log fanned out over SSE and WebSocket
+//
+// One task per API process tails the log by id and broadcasts every change; each subscriber
+// filters the broadcast by room or address. A subscriber resuming from an id (`Last-Event-ID`
+// or `after`) first replays what it missed from the table, so reconnecting loses nothing.
+// One that falls too far behind the broadcast is disconnected and resumes the same way.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::Response;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use serde::Deserialize;
+use sui_indexer_alt_framework::postgres::Db;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::MissedTickBehavior;
+use tracing::warn;
+use utoipa::IntoParams;
+
+use crate::db::models::RoomChange;
+use crate::db::queries::{self, ChangeFilter};
+
+/// Changes buffered for subscribers before the slowest are disconnected
+const BROADCAST_CAPACITY: usize = 4096;
+/// Changes read from the log per query, when tailing or replaying
+const PAGE_SIZE: i64 = 500;
+/// Changes buffered per subscriber connection
+const SUBSCRIBER_CAPACITY: usize = 256;
+
+/// Handle to the broadcast of committed changes
+#[derive(Clone)]
+pub struct ChangeFeed {
+    sender: broadcast::Sender<Arc<RoomChange>>,
+}
+
+impl ChangeFeed {
+    /// Tail the change log from its current end, polling every `poll_interval`
+    pub async fn start(db: Db, poll_interval: Duration) -> anyhow::Result<Self> {
+        let (sender, _) = broadcast::channel(BROADCAST_CAPACITY);
+        let mut last_id = queries::latest_change_id(&mut db.connect().await?).await?;
+
+        let feed = ChangeFeed { sender: sender.clone() };
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(poll_interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+
+                // Drain everything committed since the last poll, a page at a time
+                loop {
+                    let changes = match changes_after(&db, &ChangeFilter::default(), last_id).await {
+                        Ok(changes) => changes,
+                        Err(e) => {
+                            warn!("Failed to read room_changes: {e:#}");
+                            break;
+                        }
+                    };
+
+                    let drained = (changes.len() as i64) < PAGE_SIZE;
+                    for change in changes {
+                        last_id = change.id;
+                        // No subscribers is not an error
+                        let _ = sender.send(Arc::new(change));
+                    }
+
+                    if drained {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(feed)
+    }
+
+    /// Changes matching `filter`, from after `after` if given, else from now on. The stream
+    /// ends if the subscriber falls behind the broadcast.
+    pub fn subscribe(
+        &self,
+        db: Db,
+        filter: ChangeFilter,
+        after: Option<i64>,
+    ) -> BoxStream<'static, Arc<RoomChange>> {
+        // Subscribe before replaying, so nothing committed during the replay is missed
+        let receiver = self.sender.subscribe();
+        let (sender, changes) = mpsc::channel(SUBSCRIBER_CAPACITY);
+
+        tokio::spawn(async move {
+            if let Err(e) = forward(db, receiver, filter, after, sender).await {
+                warn!("Change subscriber stopped: {e:#}");
+            }
+        });
+
+        stream::unfold(changes, |mut changes| async move {
+            changes.recv().await.map(|change| (change, changes))
+        })
+        .boxed()
+    }
+}
+
+async fn changes_after(db: &Db, filter: &ChangeFilter, after: i64) -> anyhow::Result<Vec<RoomChange>> {
+    let mut conn = db.connect().await?;
+    queries::room_changes_after(&mut conn, filter, after, PAGE_SIZE).await
+}
+
+/// Replay the log after `after`, then pass on matching broadcast changes until the
+/// subscriber goes away or falls behind
+async fn forward(
+    db: Db,
+    mut receiver: broadcast::Receiver<Arc<RoomChange>>,
+    filter: ChangeFilter,
+    after: Option<i64>,
+    sender: mpsc::Sender<Arc<RoomChange>>,
+) -> anyhow::Result<()> {
+    let mut last_id = 0;
+
+    if let Some(after) = after {
+        last_id = after;
+        loop {
+            let changes = changes_after(&db, &filter, last_id).await?;
+            let drained = (changes.len() as i64) < PAGE_SIZE;
+
+            for change in changes {
+                last_id = change.id;
+                if sender.send(Arc::new(change)).await.is_err() {
+                    return Ok(());
+                }
+            }
+
+            if drained {
+                break;
+            }
+        }
+    }
+
+    loop {
+        let change = match receiver.recv().await {
+            Ok(change) => change,
+            // The client resumes from the last id it saw
+            Err(broadcast::error::RecvError::Lagged(_)) => return Ok(()),
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        };
+
+        if sender.is_closed() {
+            return Ok(());
+        }
+
+        // Already replayed from the table
+        if change.id <= last_id || !filter.matches(&change) {
+            continue;
+        }
+
+        if sender.send(change).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+#[into_params(parameter_in = Query)]
+pub(super) struct ChangesQuery {
+    /// Only changes of this room
+    room_id: Option<String>,
+    /// Only changes concerning this address
+    address: Option<String>,
+    /// Replay changes after this id first; SSE clients resume with `Last-Event-ID` instead
+    after: Option<i64>,
+}
+
+impl ChangesQuery {
+    fn filter(&self) -> ChangeFilter {
+        ChangeFilter {
+            room_id: self.room_id.clone(),
+            address: self.address.clone(),
+        }
+    }
+}
+
+/// Committed changes as Server-Sent Events, each with the change id as its event id
+#[utoipa::path(
+    get,
+    path = "/changes",
+    params(ChangesQuery),
+    responses((status = 200, description = "Stream of changes", body = RoomChange, content_type = "text/event-stream"))
+)]
+pub(super) async fn changes_sse(
+    State(feed): State<ChangeFeed>,
+    State(db): State<Db>,
+    headers: HeaderMap,
+    Query(query): Query<ChangesQuery>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    // A reconnecting EventSource repeats the original URL, so its Last-Event-ID wins
+    let after = headers
+        .get("last-event-id")
+        .and_then(|id| id.to_str().ok())
+        .and_then(|id| id.parse().ok())
+        .or(query.after);
+
+    let events = feed
+        .subscribe(db, query.filter(), after)
+        .map(|change| Event::default().id(change.id.to_string()).json_data(&*change));
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Committed changes over a WebSocket, one JSON text message per change
+pub(super) async fn changes_ws(
+    ws: WebSocketUpgrade,
+    State(feed): State<ChangeFeed>,
+    State(db): State<Db>,
+    Query(query): Query<ChangesQuery>,
+) -> Response {
+    let filter = query.filter();
+    ws.on_upgrade(move |socket| send_changes(socket, feed.subscribe(db, filter, query.after)))
+}
+
+async fn send_changes(mut socket: WebSocket, mut changes: BoxStream<'static, Arc<RoomChange>>) {
+    loop {
+        tokio::select! {
+            change = changes.next() => {
+                let Some(change) = change else {
+                    break;
+                };
+                let Ok(text) = serde_json::to_string(&*change) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    return;
+                }
+            }
+            // Incoming messages are ignored; only a close or error ends the stream
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    let _ = socket.send(Message::Close(None)).await;
+}
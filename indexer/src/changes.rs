@@ -0,0 +1,227 @@
+// Room change log - committed changes for live subscribers
+//
+// Each pipeline commit appends what it changed to `room_changes` in the same transaction,
+// so a change becomes visible exactly when the rows it describes do. The read API tails the
+// table by id and streams it over SSE and WebSocket (`api::stream`).
+//
+// Each appended change is also announced with `pg_notify` in the same transaction, so services
+// reading the tables directly can `LISTEN` for invalidations. Postgres delivers notifications
+// only once the transaction commits, in the order they were sent.
+//
+// Delivery is at-least-once: a replayed checkpoint appends its participant changes again.
+// Rooms are only appended when their object version advances, and events when their
+// `room_events` row is first written.
+//
+// The log is a feed, not history: `prune_changes` drops entries older than the retention
+// window, except deletions, which `--retry-dead-letters` checks to tell a stale letter.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use diesel::dsl::IntervalDsl;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, Text};
+use diesel_async::RunQueryDsl;
+use serde_json::Value;
+use sui_indexer_alt_framework::postgres::{self, Db};
+use tracing::{info, warn};
+
+use crate::db::max_insert_rows;
+use crate::db::models::{NewRoomChange, NewRoomEvent, NewRoomParticipant};
+use crate::db::schema::room_changes;
+use crate::utils::Provenance;
+
+pub const ROOM_UPSERTED: &str = "ROOM_UPSERTED";
+pub const ROOM_DELETED: &str = "ROOM_DELETED";
+pub const PARTICIPANT_JOINED: &str = "PARTICIPANT_JOINED";
+pub const PARTICIPANT_REVOKED: &str = "PARTICIPANT_REVOKED";
+pub const PARTICIPANT_APPROVED: &str = "PARTICIPANT_APPROVED";
+pub const METADATA_UPDATED: &str = "METADATA_UPDATED";
+pub const METADATA_DELETED: &str = "METADATA_DELETED";
+pub const EVENT: &str = "EVENT";
+
+/// Room and participant changes
+pub const ROOMS_CHANNEL: &str = "suimeet_rooms";
+/// Room metadata changes
+pub const METADATA_CHANNEL: &str = "suimeet_room_metadata";
+/// Newly indexed events
+pub const EVENTS_CHANNEL: &str = "suimeet_room_events";
+
+/// Held until commit, so appends from concurrent pipelines commit in `id` order and a reader
+/// tailing by id never passes a row that a slower transaction commits later
+const CHANGE_LOG_LOCK_SQL: &str = "SELECT pg_advisory_xact_lock(hashtext('room_changes'))";
+
+/// One notification per (channel, payload) pair; `unnest` yields them in array order
+const NOTIFY_SQL: &str = "
+    SELECT pg_notify(n.channel, n.payload)
+    FROM unnest($1::TEXT[], $2::TEXT[]) AS n(channel, payload)";
+
+/// `LISTEN` channel a change is announced on
+pub fn channel(change_kind: &str) -> &'static str {
+    match change_kind {
+        METADATA_UPDATED | METADATA_DELETED => METADATA_CHANNEL,
+        EVENT => EVENTS_CHANNEL,
+        _ => ROOMS_CHANNEL,
+    }
+}
+
+/// Notification payload: small, since Postgres caps payloads at 8000 bytes. Listeners read
+/// the rest from the tables, or from `room_changes` by `change_id`.
+fn notify_payload(change_id: i64, change: &NewRoomChange) -> String {
+    serde_json::json!({
+        "change_id": change_id,
+        "room_id": change.room_id,
+        "change_kind": change.change_kind,
+        "checkpoint": change.checkpoint_sequence_number,
+    })
+    .to_string()
+}
+
+impl NewRoomChange {
+    pub fn new(
+        room_id: &str,
+        change_kind: &str,
+        addresses: Vec<String>,
+        data: Value,
+        provenance: &Provenance,
+    ) -> Self {
+        NewRoomChange {
+            room_id: room_id.to_string(),
+            change_kind: change_kind.to_string(),
+            addresses,
+            data,
+            checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+            transaction_digest: provenance.transaction_digest.clone(),
+        }
+    }
+
+    /// A participant (back) in its room
+    pub fn participant_joined(participant: &NewRoomParticipant) -> Self {
+        NewRoomChange {
+            room_id: participant.room_id.clone(),
+            change_kind: PARTICIPANT_JOINED.to_string(),
+            addresses: vec![participant.participant_address.clone()],
+            data: serde_json::json!({
+                "participant_address": participant.participant_address,
+                "role": participant.role,
+            }),
+            checkpoint_sequence_number: participant.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: participant.checkpoint_timestamp_ms,
+            transaction_digest: participant.transaction_digest.clone(),
+        }
+    }
+
+    /// A decoded event, addressed to its sender
+    pub fn event(event: &NewRoomEvent) -> Self {
+        NewRoomChange {
+            room_id: event.room_id.clone(),
+            change_kind: EVENT.to_string(),
+            addresses: vec![event.sender.clone()],
+            data: serde_json::json!({
+                "event_type": event.event_type,
+                "event_index": event.event_index,
+                "data": event.data,
+            }),
+            checkpoint_sequence_number: event.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: event.checkpoint_timestamp_ms,
+            transaction_digest: event.transaction_digest.clone(),
+        }
+    }
+}
+
+/// Append changes to the log and announce them. Call last in a commit: the lock it takes
+/// is held until the transaction ends, so the other pipelines wait only for the commit itself.
+pub async fn append_changes(changes: &[NewRoomChange], conn: &mut postgres::Connection<'_>) -> Result<usize> {
+    if changes.is_empty() {
+        return Ok(0);
+    }
+
+    diesel::sql_query(CHANGE_LOG_LOCK_SQL).execute(conn).await?;
+
+    let mut change_ids: Vec<i64> = Vec::with_capacity(changes.len());
+    for chunk in changes.chunks(max_insert_rows::<NewRoomChange>()) {
+        change_ids.extend(
+            diesel::insert_into(room_changes::table)
+                .values(chunk)
+                .returning(room_changes::id)
+                .get_results::<i64>(conn)
+                .await?,
+        );
+    }
+
+    // RETURNING lists the inserted rows in VALUES order
+    let (channels, payloads): (Vec<String>, Vec<String>) = change_ids
+        .iter()
+        .zip(changes)
+        .map(|(change_id, change)| {
+            (channel(&change.change_kind).to_string(), notify_payload(*change_id, change))
+        })
+        .unzip();
+
+    diesel::sql_query(NOTIFY_SQL)
+        .bind::<Array<Text>, _>(channels)
+        .bind::<Array<Text>, _>(payloads)
+        .execute(conn)
+        .await?;
+
+    Ok(change_ids.len())
+}
+
+/// Delete changes older than `retention`, keeping room and metadata deletions
+pub async fn prune_changes(retention: Duration, conn: &mut postgres::Connection<'_>) -> Result<usize> {
+    let pruned = diesel::delete(room_changes::table)
+        .filter(room_changes::created_at.lt(diesel::dsl::now - (retention.as_secs() as i64).seconds()))
+        .filter(room_changes::change_kind.ne_all(vec![ROOM_DELETED, METADATA_DELETED]))
+        .execute(conn)
+        .await?;
+
+    Ok(pruned)
+}
+
+/// Prune the change log every `interval`, for as long as the indexer runs
+pub async fn prune_changes_periodically(db: Db, retention: Duration, interval: Duration) {
+    loop {
+        match db.connect().await {
+            Ok(mut conn) => match prune_changes(retention, &mut conn).await {
+                Ok(pruned) => info!(pruned, "Pruned room changes"),
+                Err(e) => warn!("Failed to prune room changes: {e:#}"),
+            },
+            Err(e) => warn!("Failed to connect to prune room changes: {e:#}"),
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_channel_and_payload() {
+        assert_eq!(channel(PARTICIPANT_APPROVED), ROOMS_CHANNEL);
+        assert_eq!(channel(METADATA_UPDATED), METADATA_CHANNEL);
+        assert_eq!(channel(EVENT), EVENTS_CHANNEL);
+
+        let provenance = Provenance {
+            checkpoint_sequence_number: 42,
+            checkpoint_timestamp_ms: 42_000,
+            transaction_digest: "tx42".to_string(),
+            object_version: 7,
+            package_version: Some(1),
+        };
+        let change = NewRoomChange::new("0x1", ROOM_UPSERTED, vec![], serde_json::json!({}), &provenance);
+        let payload: Value = serde_json::from_str(&notify_payload(9, &change)).unwrap();
+
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "change_id": 9,
+                "room_id": "0x1",
+                "change_kind": "ROOM_UPSERTED",
+                "checkpoint": 42,
+            })
+        );
+    }
+}
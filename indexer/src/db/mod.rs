@@ -1,2 +1,10 @@
 pub mod schema;
 pub mod models;
+pub mod queries;
+
+use sui_indexer_alt_framework::FieldCount;
+
+/// Rows per multi-row INSERT of `T`, keeping the bind parameters under Postgres' limit
+pub const fn max_insert_rows<T: FieldCount>() -> usize {
+    i16::MAX as usize / T::FIELD_COUNT
+}
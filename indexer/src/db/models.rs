@@ -1,14 +1,17 @@
+use async_graphql::SimpleObject;
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use sui_indexer_alt_framework::FieldCount;
+use utoipa::ToSchema;
 
 use super::schema::*;
 
 // ===== Queryable Models (for reading from DB) =====
 
-#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema, SimpleObject, Debug, Clone)]
 #[diesel(table_name = meeting_rooms)]
+#[graphql(complex)]
 pub struct MeetingRoom {
     pub id: i64,
     pub room_id: String,
@@ -26,6 +29,13 @@ pub struct MeetingRoom {
     pub transaction_digest: String,
     pub indexed_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub checkpoint_timestamp_ms: i64,
+    pub object_version: i64,
+    // Renamed in GraphQL, where `participants` resolves the `room_participants` rows
+    #[graphql(name = "participantAddresses")]
+    pub participants: Vec<String>,
+    pub description: Option<String>,
+    pub package_version: Option<i64>,
 }
 
 // ===== Insertable Models (for writing to DB) =====
@@ -45,25 +55,19 @@ pub struct NewMeetingRoom {
     pub started_at: Option<i64>,
     pub ended_at: Option<i64>,
     pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
     pub transaction_digest: String,
-}
-
-// ===== AsChangeset Models (for updates) =====
-
-#[derive(AsChangeset, Debug, Clone)]
-#[diesel(table_name = meeting_rooms)]
-pub struct MeetingRoomUpdate {
-    pub status: Option<i16>,
-    pub participant_count: Option<i32>,
-    pub started_at: Option<i64>,
-    pub ended_at: Option<i64>,
-    pub hosts: Option<Vec<String>>,
+    pub object_version: i64,
+    pub participants: Vec<String>,
+    pub description: Option<String>,
+    pub package_version: Option<i64>,
 }
 
 // ===== Room Participants Models =====
 
-#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[derive(Queryable, QueryableByName, Selectable, Serialize, Deserialize, ToSchema, SimpleObject, Debug, Clone)]
 #[diesel(table_name = room_participants)]
+#[graphql(complex)]
 pub struct RoomParticipant {
     pub id: i64,
     pub room_id: String,
@@ -72,6 +76,15 @@ pub struct RoomParticipant {
     pub admin_cap_id: Option<String>,
     pub joined_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub status: String,
+    pub left_at: Option<NaiveDateTime>,
+    pub approved_by: Option<String>,
+    pub revoked_by: Option<String>,
+    pub package_version: Option<i64>,
 }
 
 #[derive(Insertable, Debug, Clone, FieldCount)]
@@ -81,11 +94,17 @@ pub struct NewRoomParticipant {
     pub participant_address: String,
     pub role: String,
     pub admin_cap_id: Option<String>,
+    pub joined_at: NaiveDateTime,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub package_version: Option<i64>,
 }
 
 // ===== Room Metadata Models =====
 
-#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema, SimpleObject, Debug, Clone)]
 #[diesel(table_name = room_metadata)]
 pub struct RoomMetadata {
     pub id: i64,
@@ -94,9 +113,15 @@ pub struct RoomMetadata {
     pub df_version: i64,
     pub language: String,
     pub timezone: String,
+    #[schema(value_type = Option<String>)]
     pub recording_blob_id: Option<bigdecimal::BigDecimal>,
     pub indexed_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub package_version: Option<i64>,
 }
 
 #[derive(Insertable, Debug, Clone, FieldCount)]
@@ -108,4 +133,372 @@ pub struct NewRoomMetadata {
     pub language: String,
     pub timezone: String,
     pub recording_blob_id: Option<bigdecimal::BigDecimal>,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub package_version: Option<i64>,
+}
+
+// ===== Room Events Models =====
+
+#[derive(Queryable, QueryableByName, Selectable, Serialize, Deserialize, SimpleObject, Debug, Clone)]
+#[diesel(table_name = room_events)]
+#[graphql(complex)]
+pub struct RoomEvent {
+    pub id: i64,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub event_index: i64,
+    pub event_type: String,
+    pub room_id: String,
+    pub sender: String,
+    pub package_id: String,
+    #[graphql(skip)]
+    pub data: serde_json::Value,
+    pub indexed_at: NaiveDateTime,
+    pub package_version: Option<i64>,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = room_events)]
+pub struct NewRoomEvent {
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub event_index: i64,
+    pub event_type: String,
+    pub room_id: String,
+    pub sender: String,
+    pub package_id: String,
+    pub data: serde_json::Value,
+    pub package_version: Option<i64>,
+}
+
+// ===== Host Caps Models =====
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = host_caps)]
+pub struct HostCap {
+    pub id: i64,
+    pub cap_id: String,
+    pub room_id: String,
+    pub granted_at: i64,
+    pub owner_kind: String,
+    pub owner_address: Option<String>,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub indexed_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub package_version: Option<i64>,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = host_caps)]
+pub struct NewHostCap {
+    pub cap_id: String,
+    pub room_id: String,
+    pub granted_at: i64,
+    pub owner_kind: String,
+    pub owner_address: Option<String>,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub package_version: Option<i64>,
+}
+
+// ===== HostCap History Models =====
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = host_cap_history)]
+pub struct HostCapChange {
+    pub id: i64,
+    pub cap_id: String,
+    pub room_id: String,
+    pub change_kind: String,
+    pub previous_owner_kind: Option<String>,
+    pub previous_owner_address: Option<String>,
+    pub owner_kind: Option<String>,
+    pub owner_address: Option<String>,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub indexed_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = host_cap_history)]
+pub struct NewHostCapChange {
+    pub cap_id: String,
+    pub room_id: String,
+    pub change_kind: String,
+    pub previous_owner_kind: Option<String>,
+    pub previous_owner_address: Option<String>,
+    pub owner_kind: Option<String>,
+    pub owner_address: Option<String>,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+}
+
+// ===== Room Metadata History Models =====
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = room_metadata_history)]
+pub struct RoomMetadataVersion {
+    pub id: i64,
+    pub room_id: String,
+    pub dynamic_field_id: String,
+    pub df_version: i64,
+    pub language: String,
+    pub timezone: String,
+    pub recording_blob_id: Option<bigdecimal::BigDecimal>,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub indexed_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = room_metadata_history)]
+pub struct NewRoomMetadataVersion {
+    pub room_id: String,
+    pub dynamic_field_id: String,
+    pub df_version: i64,
+    pub language: String,
+    pub timezone: String,
+    pub recording_blob_id: Option<bigdecimal::BigDecimal>,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+}
+
+// ===== Room Whitelist Models =====
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = room_whitelist)]
+pub struct RoomWhitelist {
+    pub id: i64,
+    pub room_id: String,
+    pub seal_policy_id: String,
+    pub whitelist: Vec<String>,
+    pub whitelist_updated_at: i64,
+    pub missing_from_whitelist: Vec<String>,
+    pub extra_in_whitelist: Vec<String>,
+    pub has_drift: bool,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub indexed_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = room_whitelist)]
+pub struct NewRoomWhitelist {
+    pub room_id: String,
+    pub seal_policy_id: String,
+    pub whitelist: Vec<String>,
+    pub whitelist_updated_at: i64,
+    pub missing_from_whitelist: Vec<String>,
+    pub extra_in_whitelist: Vec<String>,
+    pub has_drift: bool,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+}
+
+// ===== Room Registry Models =====
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = room_registry)]
+pub struct RoomRegistry {
+    pub id: i64,
+    pub registry_id: String,
+    pub room_count: i64,
+    pub all_rooms: Vec<String>,
+    pub active_rooms: Vec<String>,
+    pub scheduled_rooms: Vec<String>,
+    pub rooms_by_host_table_id: String,
+    pub rooms_by_seal_id_table_id: String,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub indexed_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = room_registry)]
+pub struct NewRoomRegistry {
+    pub registry_id: String,
+    pub room_count: i64,
+    pub all_rooms: Vec<String>,
+    pub active_rooms: Vec<String>,
+    pub scheduled_rooms: Vec<String>,
+    pub rooms_by_host_table_id: String,
+    pub rooms_by_seal_id_table_id: String,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = room_registry_snapshots)]
+pub struct NewRoomRegistrySnapshot {
+    pub registry_id: String,
+    pub room_count: i64,
+    pub all_rooms: Vec<String>,
+    pub active_rooms: Vec<String>,
+    pub scheduled_rooms: Vec<String>,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = registry_host_rooms)]
+pub struct RegistryHostRooms {
+    pub id: i64,
+    pub dynamic_field_id: String,
+    pub table_id: String,
+    pub host_address: String,
+    pub room_ids: Vec<String>,
+    pub checkpoint_sequence_number: i64,
+    pub transaction_digest: String,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = registry_seal_rooms)]
+pub struct RegistrySealRoom {
+    pub id: i64,
+    pub dynamic_field_id: String,
+    pub table_id: String,
+    pub seal_policy_id: String,
+    pub room_id: String,
+    pub checkpoint_sequence_number: i64,
+    pub transaction_digest: String,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = registry_host_rooms)]
+pub struct NewRegistryHostRooms {
+    pub dynamic_field_id: String,
+    pub table_id: String,
+    pub host_address: String,
+    pub room_ids: Vec<String>,
+    pub checkpoint_sequence_number: i64,
+    pub transaction_digest: String,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = registry_seal_rooms)]
+pub struct NewRegistrySealRoom {
+    pub dynamic_field_id: String,
+    pub table_id: String,
+    pub seal_policy_id: String,
+    pub room_id: String,
+    pub checkpoint_sequence_number: i64,
+    pub transaction_digest: String,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = meeting_rooms_registry)]
+pub struct MeetingRoomRegistryView {
+    pub room_id: String,
+    pub title: String,
+    pub status: i16,
+    /// None until the registry has been indexed
+    pub registry_id: Option<String>,
+    pub registry_status: String,
+    pub registry_consistent: bool,
+}
+
+// ===== Dead Letter Models =====
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = indexer_dead_letters)]
+pub struct DeadLetter {
+    pub id: i64,
+    pub pipeline: String,
+    pub kind: String,
+    pub item_key: String,
+    pub type_tag: String,
+    pub object_id: Option<String>,
+    pub event_index: Option<i64>,
+    /// LIVE, WRAPPED or DELETED for objects, None for events
+    pub object_state: Option<String>,
+    pub raw_bytes: Vec<u8>,
+    pub error: String,
+    pub status: String,
+    pub retry_count: i32,
+    pub last_retry_at: Option<NaiveDateTime>,
+    pub resolved_at: Option<NaiveDateTime>,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub package_version: Option<i64>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = indexer_dead_letters)]
+pub struct NewDeadLetter {
+    pub pipeline: String,
+    pub kind: String,
+    pub item_key: String,
+    pub type_tag: String,
+    pub object_id: Option<String>,
+    pub event_index: Option<i64>,
+    pub object_state: Option<String>,
+    pub raw_bytes: Vec<u8>,
+    pub error: String,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    pub package_version: Option<i64>,
+}
+
+// ===== Room Change Log Models =====
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema, Debug, Clone)]
+#[diesel(table_name = room_changes)]
+pub struct RoomChange {
+    pub id: i64,
+    pub room_id: String,
+    pub change_kind: String,
+    pub addresses: Vec<String>,
+    #[schema(value_type = Object)]
+    pub data: serde_json::Value,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone, FieldCount)]
+#[diesel(table_name = room_changes)]
+pub struct NewRoomChange {
+    pub room_id: String,
+    pub change_kind: String,
+    pub addresses: Vec<String>,
+    pub data: serde_json::Value,
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
 }
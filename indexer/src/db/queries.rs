@@ -0,0 +1,497 @@
+// Read queries over the indexed tables
+
+use std::collections::{HashMap, HashSet};
+use anyhow::Result;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Float4, Int2, Nullable, Text};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::models::{
+    MeetingRoom, MeetingRoomRegistryView, RoomChange, RoomEvent, RoomMetadata, RoomMetadataVersion,
+    RoomParticipant, RoomWhitelist,
+};
+use super::schema::{
+    meeting_rooms, meeting_rooms_registry, room_changes, room_events, room_metadata, room_metadata_history,
+    room_participants, room_whitelist,
+};
+
+/// One page of a keyset-paginated query
+///
+/// Pages are keyed on the table's `id`, so rows indexed while a client pages through never
+/// shift or repeat entries. `next_cursor` is `None` on the last page.
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<i64>,
+}
+
+impl<T> Page<T> {
+    /// Build a page from up to `limit + 1` rows; the extra row only signals that more follow
+    fn from_rows(mut rows: Vec<T>, limit: i64, key: impl Fn(&T) -> i64) -> Self {
+        let next_cursor = if rows.len() as i64 > limit {
+            rows.truncate(limit as usize);
+            rows.last().map(key)
+        } else {
+            None
+        };
+
+        Page { items: rows, next_cursor }
+    }
+}
+
+/// One page for each of `room_ids`, from rows holding up to `limit + 1` of each room in page
+/// order
+fn pages_by_room<T>(
+    room_ids: &[String],
+    rows: Vec<T>,
+    limit: i64,
+    room_id: fn(&T) -> &str,
+    key: fn(&T) -> i64,
+) -> HashMap<String, Page<T>> {
+    let mut by_room: HashMap<String, Vec<T>> = room_ids.iter().map(|id| (id.clone(), Vec::new())).collect();
+    for row in rows {
+        if let Some(rows) = by_room.get_mut(room_id(&row)) {
+            rows.push(row);
+        }
+    }
+
+    by_room
+        .into_iter()
+        .map(|(room_id, rows)| (room_id, Page::from_rows(rows, limit, key)))
+        .collect()
+}
+
+/// Filters for `list_rooms`; unset filters match every room
+#[derive(Debug, Clone, Default)]
+pub struct RoomFilter {
+    pub status: Option<i16>,
+    pub host: Option<String>,
+    /// Address with an `ACTIVE` row in `room_participants`
+    pub participant: Option<String>,
+    /// Inclusive lower bound on `created_at` (ms)
+    pub created_after: Option<i64>,
+    /// Exclusive upper bound on `created_at` (ms)
+    pub created_before: Option<i64>,
+}
+
+/// A room by its object ID
+pub async fn room_by_id(conn: &mut AsyncPgConnection, room_id: &str) -> Result<Option<MeetingRoom>> {
+    let room = meeting_rooms::table
+        .filter(meeting_rooms::room_id.eq(room_id))
+        .select(MeetingRoom::as_select())
+        .first(conn)
+        .await
+        .optional()?;
+
+    Ok(room)
+}
+
+/// The rooms among `room_ids` that are indexed, in no particular order
+pub async fn rooms_by_ids(conn: &mut AsyncPgConnection, room_ids: &[String]) -> Result<Vec<MeetingRoom>> {
+    let rooms = meeting_rooms::table
+        .filter(meeting_rooms::room_id.eq_any(room_ids))
+        .select(MeetingRoom::as_select())
+        .load(conn)
+        .await?;
+
+    Ok(rooms)
+}
+
+/// Rooms matching `filter`, most recently indexed first, after `cursor`
+pub async fn list_rooms(
+    conn: &mut AsyncPgConnection,
+    filter: &RoomFilter,
+    cursor: Option<i64>,
+    limit: i64,
+) -> Result<Page<MeetingRoom>> {
+    let mut query = meeting_rooms::table
+        .select(MeetingRoom::as_select())
+        .order(meeting_rooms::id.desc())
+        .limit(limit + 1)
+        .into_boxed();
+
+    if let Some(cursor) = cursor {
+        query = query.filter(meeting_rooms::id.lt(cursor));
+    }
+    if let Some(status) = filter.status {
+        query = query.filter(meeting_rooms::status.eq(status));
+    }
+    if let Some(created_after) = filter.created_after {
+        query = query.filter(meeting_rooms::created_at.ge(created_after));
+    }
+    if let Some(created_before) = filter.created_before {
+        query = query.filter(meeting_rooms::created_at.lt(created_before));
+    }
+    if let Some(host) = &filter.host {
+        query = query.filter(meeting_rooms::hosts.contains(vec![host.clone()]));
+    }
+    if let Some(participant) = &filter.participant {
+        query = query.filter(
+            meeting_rooms::room_id.eq_any(
+                room_participants::table
+                    .filter(room_participants::participant_address.eq(participant.clone()))
+                    .filter(room_participants::status.eq("ACTIVE"))
+                    .select(room_participants::room_id),
+            ),
+        );
+    }
+
+    let rooms = query.load(conn).await?;
+    Ok(Page::from_rows(rooms, limit, |room| room.id))
+}
+
+/// Participants of a room, revoked ones included, in the order they were first indexed
+pub async fn room_participants(
+    conn: &mut AsyncPgConnection,
+    room_id: &str,
+    cursor: Option<i64>,
+    limit: i64,
+) -> Result<Page<RoomParticipant>> {
+    let mut query = room_participants::table
+        .filter(room_participants::room_id.eq(room_id))
+        .select(RoomParticipant::as_select())
+        .order(room_participants::id.asc())
+        .limit(limit + 1)
+        .into_boxed();
+
+    if let Some(cursor) = cursor {
+        query = query.filter(room_participants::id.gt(cursor));
+    }
+
+    let participants = query.load(conn).await?;
+    Ok(Page::from_rows(participants, limit, |participant| participant.id))
+}
+
+/// Up to `$3` participant rows of each room in `$1`, after the cursor `$2`, as
+/// `room_participants` pages them
+const ROOMS_PARTICIPANTS_SQL: &str = "
+    SELECT p.*
+    FROM unnest($1::TEXT[]) AS r(room_id)
+    CROSS JOIN LATERAL (
+        SELECT * FROM room_participants rp
+        WHERE rp.room_id = r.room_id
+          AND ($2::BIGINT IS NULL OR rp.id > $2)
+        ORDER BY rp.id
+        LIMIT $3
+    ) p
+    ORDER BY p.room_id, p.id";
+
+/// `room_participants` for each of `room_ids`, in one query
+pub async fn rooms_participants(
+    conn: &mut AsyncPgConnection,
+    room_ids: &[String],
+    cursor: Option<i64>,
+    limit: i64,
+) -> Result<HashMap<String, Page<RoomParticipant>>> {
+    let participants: Vec<RoomParticipant> = diesel::sql_query(ROOMS_PARTICIPANTS_SQL)
+        .bind::<Array<Text>, _>(room_ids)
+        .bind::<Nullable<BigInt>, _>(cursor)
+        .bind::<BigInt, _>(limit + 1)
+        .load(conn)
+        .await?;
+
+    Ok(pages_by_room(room_ids, participants, limit, |p| p.room_id.as_str(), |p| p.id))
+}
+
+/// Events of a room, newest first, optionally within a checkpoint time range (ms, `until_ms`
+/// exclusive)
+pub async fn room_events(
+    conn: &mut AsyncPgConnection,
+    room_id: &str,
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    cursor: Option<i64>,
+    limit: i64,
+) -> Result<Page<RoomEvent>> {
+    let mut query = room_events::table
+        .filter(room_events::room_id.eq(room_id))
+        .select(RoomEvent::as_select())
+        .order(room_events::id.desc())
+        .limit(limit + 1)
+        .into_boxed();
+
+    if let Some(since_ms) = since_ms {
+        query = query.filter(room_events::checkpoint_timestamp_ms.ge(since_ms));
+    }
+    if let Some(until_ms) = until_ms {
+        query = query.filter(room_events::checkpoint_timestamp_ms.lt(until_ms));
+    }
+    if let Some(cursor) = cursor {
+        query = query.filter(room_events::id.lt(cursor));
+    }
+
+    let events = query.load(conn).await?;
+    Ok(Page::from_rows(events, limit, |event| event.id))
+}
+
+/// Up to `$5` events of each room in `$1` with a checkpoint time in [`$2`, `$3`), before the
+/// cursor `$4`, as `room_events` pages them
+const ROOMS_EVENTS_SQL: &str = "
+    SELECT e.*
+    FROM unnest($1::TEXT[]) AS r(room_id)
+    CROSS JOIN LATERAL (
+        SELECT * FROM room_events re
+        WHERE re.room_id = r.room_id
+          AND ($2::BIGINT IS NULL OR re.checkpoint_timestamp_ms >= $2)
+          AND ($3::BIGINT IS NULL OR re.checkpoint_timestamp_ms < $3)
+          AND ($4::BIGINT IS NULL OR re.id < $4)
+        ORDER BY re.id DESC
+        LIMIT $5
+    ) e
+    ORDER BY e.room_id, e.id DESC";
+
+/// `room_events` for each of `room_ids`, in one query
+pub async fn rooms_events(
+    conn: &mut AsyncPgConnection,
+    room_ids: &[String],
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    cursor: Option<i64>,
+    limit: i64,
+) -> Result<HashMap<String, Page<RoomEvent>>> {
+    let events: Vec<RoomEvent> = diesel::sql_query(ROOMS_EVENTS_SQL)
+        .bind::<Array<Text>, _>(room_ids)
+        .bind::<Nullable<BigInt>, _>(since_ms)
+        .bind::<Nullable<BigInt>, _>(until_ms)
+        .bind::<Nullable<BigInt>, _>(cursor)
+        .bind::<BigInt, _>(limit + 1)
+        .load(conn)
+        .await?;
+
+    Ok(pages_by_room(room_ids, events, limit, |e| e.room_id.as_str(), |e| e.id))
+}
+
+/// Subscriber filter on the change log; unset filters match every change
+#[derive(Debug, Clone, Default)]
+pub struct ChangeFilter {
+    pub room_id: Option<String>,
+    /// Address in the change's `addresses`
+    pub address: Option<String>,
+}
+
+impl ChangeFilter {
+    pub fn matches(&self, change: &RoomChange) -> bool {
+        self.room_id.as_ref().is_none_or(|room_id| *room_id == change.room_id)
+            && self.address.as_ref().is_none_or(|address| change.addresses.contains(address))
+    }
+}
+
+/// Changes after `after` matching `filter`, oldest first
+pub async fn room_changes_after(
+    conn: &mut AsyncPgConnection,
+    filter: &ChangeFilter,
+    after: i64,
+    limit: i64,
+) -> Result<Vec<RoomChange>> {
+    let mut query = room_changes::table
+        .filter(room_changes::id.gt(after))
+        .select(RoomChange::as_select())
+        .order(room_changes::id.asc())
+        .limit(limit)
+        .into_boxed();
+
+    if let Some(room_id) = &filter.room_id {
+        query = query.filter(room_changes::room_id.eq(room_id.clone()));
+    }
+    if let Some(address) = &filter.address {
+        query = query.filter(room_changes::addresses.contains(vec![address.clone()]));
+    }
+
+    Ok(query.load(conn).await?)
+}
+
+/// Id of the latest change, 0 if there is none
+pub async fn latest_change_id(conn: &mut AsyncPgConnection) -> Result<i64> {
+    let id: Option<i64> = room_changes::table
+        .select(diesel::dsl::max(room_changes::id))
+        .first(conn)
+        .await?;
+
+    Ok(id.unwrap_or_default())
+}
+
+/// Current metadata of a room, if it has any
+pub async fn room_metadata(conn: &mut AsyncPgConnection, room_id: &str) -> Result<Option<RoomMetadata>> {
+    let metadata = room_metadata::table
+        .filter(room_metadata::room_id.eq(room_id))
+        .order(room_metadata::checkpoint_sequence_number.desc())
+        .select(RoomMetadata::as_select())
+        .first(conn)
+        .await
+        .optional()?;
+
+    Ok(metadata)
+}
+
+/// Current metadata of each of `room_ids` that has any
+pub async fn rooms_metadata(conn: &mut AsyncPgConnection, room_ids: &[String]) -> Result<Vec<RoomMetadata>> {
+    let metadata = room_metadata::table
+        .filter(room_metadata::room_id.eq_any(room_ids))
+        .select(RoomMetadata::as_select())
+        .load(conn)
+        .await?;
+
+    Ok(metadata)
+}
+
+/// List every recording a room has ever had, oldest first
+///
+/// Returns the metadata version in which each distinct `recording_blob_id` first appeared.
+pub async fn room_recordings(
+    conn: &mut AsyncPgConnection,
+    room_id: &str,
+) -> Result<Vec<RoomMetadataVersion>> {
+    let versions: Vec<RoomMetadataVersion> = room_metadata_history::table
+        .filter(room_metadata_history::room_id.eq(room_id))
+        .filter(room_metadata_history::recording_blob_id.is_not_null())
+        .order((
+            room_metadata_history::checkpoint_sequence_number.asc(),
+            room_metadata_history::df_version.asc(),
+        ))
+        .select(RoomMetadataVersion::as_select())
+        .load(conn)
+        .await?;
+
+    let mut seen = HashSet::new();
+    Ok(versions
+        .into_iter()
+        .filter(|v| seen.insert(v.recording_blob_id.clone()))
+        .collect())
+}
+
+/// Full metadata history of a room, oldest first
+pub async fn room_metadata_history(
+    conn: &mut AsyncPgConnection,
+    room_id: &str,
+) -> Result<Vec<RoomMetadataVersion>> {
+    let versions = room_metadata_history::table
+        .filter(room_metadata_history::room_id.eq(room_id))
+        .order((
+            room_metadata_history::checkpoint_sequence_number.asc(),
+            room_metadata_history::df_version.asc(),
+        ))
+        .select(RoomMetadataVersion::as_select())
+        .load(conn)
+        .await?;
+
+    Ok(versions)
+}
+
+/// Rooms whose participants and Seal whitelist currently disagree
+pub async fn rooms_with_whitelist_drift(conn: &mut AsyncPgConnection) -> Result<Vec<RoomWhitelist>> {
+    let rooms = room_whitelist::table
+        .filter(room_whitelist::has_drift.eq(true))
+        .order(room_whitelist::checkpoint_sequence_number.desc())
+        .select(RoomWhitelist::as_select())
+        .load(conn)
+        .await?;
+
+    Ok(rooms)
+}
+
+/// Indexed rooms alongside their status in the on-chain RoomRegistry
+///
+/// With `only_inconsistent`, returns just the rooms whose `MeetingRoom.status` disagrees with
+/// the registry's active/scheduled lists.
+pub async fn rooms_with_registry_status(
+    conn: &mut AsyncPgConnection,
+    only_inconsistent: bool,
+) -> Result<Vec<MeetingRoomRegistryView>> {
+    let mut query = meeting_rooms_registry::table
+        .select(MeetingRoomRegistryView::as_select())
+        .order(meeting_rooms_registry::room_id.asc())
+        .into_boxed();
+
+    if only_inconsistent {
+        query = query.filter(meeting_rooms_registry::registry_consistent.eq(false));
+    }
+
+    Ok(query.load(conn).await?)
+}
+
+/// A room matched by `search_rooms`, best match first
+#[derive(QueryableByName, Serialize, Debug, Clone)]
+pub struct RoomSearchResult {
+    #[diesel(sql_type = Text)]
+    pub room_id: String,
+    #[diesel(sql_type = Text)]
+    pub title: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub description: Option<String>,
+    #[diesel(sql_type = Int2)]
+    pub status: i16,
+    #[diesel(sql_type = BigInt)]
+    pub created_at: i64,
+    #[diesel(sql_type = Float4)]
+    pub rank: f32,
+}
+
+/// Full-text matches are ranked by `ts_rank` (title weighted above description); trigram
+/// similarity catches typos and partial words that the tsquery misses.
+const SEARCH_ROOMS_SQL: &str = "
+    SELECT room_id, title, description, status, created_at,
+        (ts_rank(search_vector, websearch_to_tsquery('simple', $1))
+            + 0.5 * GREATEST(similarity(title, $1), similarity(coalesce(description, ''), $1)))::REAL AS rank
+    FROM meeting_rooms
+    WHERE search_vector @@ websearch_to_tsquery('simple', $1)
+       OR title % $1
+       OR description % $1
+    ORDER BY rank DESC, created_at DESC
+    LIMIT $2";
+
+/// Ranked full-text and trigram search over room titles and descriptions
+pub async fn search_rooms(
+    conn: &mut AsyncPgConnection,
+    query: &str,
+    limit: i64,
+) -> Result<Vec<RoomSearchResult>> {
+    let results = diesel::sql_query(SEARCH_ROOMS_SQL)
+        .bind::<Text, _>(query)
+        .bind::<BigInt, _>(limit)
+        .load(conn)
+        .await?;
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_cursor_points_at_last_returned_row() {
+        let page = Page::from_rows(vec![9, 8, 7], 2, |id| *id);
+        assert_eq!(page.items, vec![9, 8]);
+        assert_eq!(page.next_cursor, Some(8));
+
+        let last = Page::from_rows(vec![6, 5], 2, |id| *id);
+        assert_eq!(last.items, vec![6, 5]);
+        assert_eq!(last.next_cursor, None);
+    }
+
+    #[test]
+    fn test_change_filter_matches_room_and_address() {
+        let change = RoomChange {
+            id: 1,
+            room_id: "0x1".to_string(),
+            change_kind: "PARTICIPANT_APPROVED".to_string(),
+            addresses: vec!["0xa".to_string(), "0xb".to_string()],
+            data: serde_json::json!({}),
+            checkpoint_sequence_number: 10,
+            checkpoint_timestamp_ms: 10_000,
+            transaction_digest: "tx10".to_string(),
+            created_at: Default::default(),
+        };
+        let filter = |room_id: Option<&str>, address: Option<&str>| ChangeFilter {
+            room_id: room_id.map(str::to_string),
+            address: address.map(str::to_string),
+        };
+
+        assert!(filter(None, None).matches(&change));
+        assert!(filter(Some("0x1"), Some("0xb")).matches(&change));
+        assert!(!filter(Some("0x2"), None).matches(&change));
+        assert!(!filter(None, Some("0xc")).matches(&change));
+    }
+}
@@ -21,6 +21,12 @@ diesel::table! {
         transaction_digest -> Varchar,
         indexed_at -> Timestamp,
         updated_at -> Timestamp,
+        checkpoint_timestamp_ms -> Int8,
+        object_version -> Int8,
+        participants -> Array<Varchar>,
+        description -> Nullable<Text>,
+        package_version -> Nullable<Int8>,
+        // search_vector (tsvector, generated) is only used through raw SQL in db::queries
     }
 }
 
@@ -37,6 +43,19 @@ diesel::table! {
         admin_cap_id -> Nullable<Varchar>,
         joined_at -> Timestamp,
         updated_at -> Timestamp,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        object_version -> Int8,
+        #[max_length = 20]
+        status -> Varchar,
+        left_at -> Nullable<Timestamp>,
+        #[max_length = 66]
+        approved_by -> Nullable<Varchar>,
+        #[max_length = 66]
+        revoked_by -> Nullable<Varchar>,
+        package_version -> Nullable<Int8>,
     }
 }
 
@@ -53,11 +72,325 @@ diesel::table! {
         recording_blob_id -> Nullable<Numeric>,
         indexed_at -> Timestamp,
         updated_at -> Timestamp,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        object_version -> Int8,
+        package_version -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    room_events (id) {
+        id -> Int8,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        event_index -> Int8,
+        #[max_length = 64]
+        event_type -> Varchar,
+        #[max_length = 66]
+        room_id -> Varchar,
+        #[max_length = 66]
+        sender -> Varchar,
+        #[max_length = 66]
+        package_id -> Varchar,
+        data -> Jsonb,
+        indexed_at -> Timestamp,
+        package_version -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    host_caps (id) {
+        id -> Int8,
+        #[max_length = 66]
+        cap_id -> Varchar,
+        #[max_length = 66]
+        room_id -> Varchar,
+        granted_at -> Int8,
+        #[max_length = 20]
+        owner_kind -> Varchar,
+        #[max_length = 66]
+        owner_address -> Nullable<Varchar>,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        object_version -> Int8,
+        indexed_at -> Timestamp,
+        updated_at -> Timestamp,
+        package_version -> Nullable<Int8>,
     }
 }
 
+diesel::table! {
+    host_cap_history (id) {
+        id -> Int8,
+        #[max_length = 66]
+        cap_id -> Varchar,
+        #[max_length = 66]
+        room_id -> Varchar,
+        #[max_length = 20]
+        change_kind -> Varchar,
+        #[max_length = 20]
+        previous_owner_kind -> Nullable<Varchar>,
+        #[max_length = 66]
+        previous_owner_address -> Nullable<Varchar>,
+        #[max_length = 20]
+        owner_kind -> Nullable<Varchar>,
+        #[max_length = 66]
+        owner_address -> Nullable<Varchar>,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        object_version -> Int8,
+        indexed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    room_metadata_history (id) {
+        id -> Int8,
+        #[max_length = 66]
+        room_id -> Varchar,
+        #[max_length = 66]
+        dynamic_field_id -> Varchar,
+        df_version -> Int8,
+        language -> Text,
+        timezone -> Text,
+        recording_blob_id -> Nullable<Numeric>,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        indexed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    room_whitelist (id) {
+        id -> Int8,
+        #[max_length = 66]
+        room_id -> Varchar,
+        #[max_length = 66]
+        seal_policy_id -> Varchar,
+        whitelist -> Array<Varchar>,
+        whitelist_updated_at -> Int8,
+        missing_from_whitelist -> Array<Varchar>,
+        extra_in_whitelist -> Array<Varchar>,
+        has_drift -> Bool,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        object_version -> Int8,
+        indexed_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    room_registry (id) {
+        id -> Int8,
+        #[max_length = 66]
+        registry_id -> Varchar,
+        room_count -> Int8,
+        all_rooms -> Array<Varchar>,
+        active_rooms -> Array<Varchar>,
+        scheduled_rooms -> Array<Varchar>,
+        #[max_length = 66]
+        rooms_by_host_table_id -> Varchar,
+        #[max_length = 66]
+        rooms_by_seal_id_table_id -> Varchar,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        object_version -> Int8,
+        indexed_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    room_registry_snapshots (id) {
+        id -> Int8,
+        #[max_length = 66]
+        registry_id -> Varchar,
+        room_count -> Int8,
+        all_rooms -> Array<Varchar>,
+        active_rooms -> Array<Varchar>,
+        scheduled_rooms -> Array<Varchar>,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        object_version -> Int8,
+        indexed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    registry_host_rooms (id) {
+        id -> Int8,
+        #[max_length = 66]
+        dynamic_field_id -> Varchar,
+        #[max_length = 66]
+        table_id -> Varchar,
+        #[max_length = 66]
+        host_address -> Varchar,
+        room_ids -> Array<Varchar>,
+        checkpoint_sequence_number -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    registry_seal_rooms (id) {
+        id -> Int8,
+        #[max_length = 66]
+        dynamic_field_id -> Varchar,
+        #[max_length = 66]
+        table_id -> Varchar,
+        #[max_length = 66]
+        seal_policy_id -> Varchar,
+        #[max_length = 66]
+        room_id -> Varchar,
+        checkpoint_sequence_number -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        updated_at -> Timestamp,
+    }
+}
+
+// View, not a table: see the create_room_registry migration
+diesel::table! {
+    meeting_rooms_registry (room_id) {
+        #[max_length = 66]
+        room_id -> Varchar,
+        title -> Text,
+        status -> Int2,
+        #[max_length = 66]
+        registry_id -> Nullable<Varchar>,
+        registry_status -> Text,
+        registry_consistent -> Bool,
+    }
+}
+
+diesel::table! {
+    indexer_dead_letters (id) {
+        id -> Int8,
+        #[max_length = 64]
+        pipeline -> Varchar,
+        #[max_length = 20]
+        kind -> Varchar,
+        #[max_length = 140]
+        item_key -> Varchar,
+        type_tag -> Text,
+        #[max_length = 66]
+        object_id -> Nullable<Varchar>,
+        event_index -> Nullable<Int8>,
+        #[max_length = 10]
+        object_state -> Nullable<Varchar>,
+        raw_bytes -> Bytea,
+        error -> Text,
+        #[max_length = 20]
+        status -> Varchar,
+        retry_count -> Int4,
+        last_retry_at -> Nullable<Timestamp>,
+        resolved_at -> Nullable<Timestamp>,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        object_version -> Int8,
+        package_version -> Nullable<Int8>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    room_changes (id) {
+        id -> Int8,
+        #[max_length = 66]
+        room_id -> Varchar,
+        #[max_length = 32]
+        change_kind -> Varchar,
+        addresses -> Array<Varchar>,
+        data -> Jsonb,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    webhook_subscriptions (id) {
+        id -> Int8,
+        url -> Text,
+        secret -> Text,
+        #[max_length = 66]
+        host_address -> Nullable<Varchar>,
+        #[max_length = 66]
+        room_id -> Nullable<Varchar>,
+        event_types -> Array<Varchar>,
+        active -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    webhook_deliveries (id) {
+        id -> Int8,
+        subscription_id -> Int8,
+        #[max_length = 64]
+        event_type -> Varchar,
+        #[max_length = 66]
+        room_id -> Varchar,
+        data -> Jsonb,
+        #[max_length = 20]
+        status -> Varchar,
+        attempts -> Int4,
+        next_attempt_at -> Timestamp,
+        last_status_code -> Nullable<Int4>,
+        last_error -> Nullable<Text>,
+        delivered_at -> Nullable<Timestamp>,
+        checkpoint_sequence_number -> Int8,
+        checkpoint_timestamp_ms -> Int8,
+        #[max_length = 64]
+        transaction_digest -> Varchar,
+        event_index -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::joinable!(room_whitelist -> meeting_rooms (room_id));
+diesel::joinable!(webhook_deliveries -> webhook_subscriptions (subscription_id));
+
 diesel::allow_tables_to_appear_in_same_query!(
     meeting_rooms,
     room_participants,
     room_metadata,
+    room_events,
+    host_caps,
+    host_cap_history,
+    room_metadata_history,
+    room_whitelist,
+    room_registry,
+    room_registry_snapshots,
+    registry_host_rooms,
+    registry_seal_rooms,
+    meeting_rooms_registry,
+    indexer_dead_letters,
+    room_changes,
+    webhook_subscriptions,
+    webhook_deliveries,
 );
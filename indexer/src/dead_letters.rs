@@ -0,0 +1,454 @@
+// Dead letters - objects and events the indexer could not decode
+//
+// Under the `dead-letter` policy a decode failure is recorded in `indexer_dead_letters`
+// together with the raw BCS bytes, and the pipeline moves on. After a decoder fix,
+// `--retry-dead-letters` re-decodes the stored bytes and commits whatever now succeeds,
+// each letter in its own transaction, as the live pipeline commits a batch.
+
+use anyhow::{bail, Result};
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::RunQueryDsl;
+use sui_indexer_alt_framework::{
+    pipeline::{Processor, sequential::Handler},
+    postgres::{self, Db},
+    store::TransactionalStore,
+    types::event::Event,
+    types::object::Object,
+};
+
+use crate::db::models::{DeadLetter, NewDeadLetter};
+use crate::db::schema::indexer_dead_letters;
+use crate::utils::Provenance;
+
+/// What a pipeline does when an object or event of ours fails to decode
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodeErrorPolicy {
+    /// Fail the checkpoint, halting the pipeline until the decoder is fixed
+    Fail,
+    /// Record the failure in `indexer_dead_letters` and keep indexing
+    #[default]
+    DeadLetter,
+}
+
+/// Where an object was in its checkpoint when it failed to decode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectState {
+    /// Final state of a live output object
+    Live,
+    /// Input object wrapped in this checkpoint
+    Wrapped,
+    /// Input object deleted in this checkpoint
+    Deleted,
+}
+
+impl ObjectState {
+    /// Input object that is not live at the end of the checkpoint
+    pub fn removed(deleted: bool) -> Self {
+        if deleted { Self::Deleted } else { Self::Wrapped }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Live => "LIVE",
+            Self::Wrapped => "WRAPPED",
+            Self::Deleted => "DELETED",
+        }
+    }
+}
+
+impl DecodeErrorPolicy {
+    /// Dead letter for an object that failed to decode, or the error itself under `Fail`
+    pub fn object_letter(
+        self,
+        pipeline: &str,
+        object: &Object,
+        state: ObjectState,
+        provenance: &Provenance,
+        error: anyhow::Error,
+    ) -> Result<NewDeadLetter> {
+        if self == Self::Fail {
+            return Err(error);
+        }
+
+        let letter = NewDeadLetter::object(pipeline, object, state, provenance, format!("{error:#}"))?;
+
+        tracing::warn!(
+            pipeline,
+            object_id = %letter.item_key,
+            type_tag = %letter.type_tag,
+            checkpoint = provenance.checkpoint_sequence_number,
+            "Dead-lettering undecodable object: {error:#}"
+        );
+
+        Ok(letter)
+    }
+
+    /// Dead letter for an event that failed to decode, or the error itself under `Fail`
+    pub fn event_letter(
+        self,
+        pipeline: &str,
+        event: &Event,
+        event_index: usize,
+        provenance: &Provenance,
+        error: anyhow::Error,
+    ) -> Result<NewDeadLetter> {
+        if self == Self::Fail {
+            return Err(error);
+        }
+
+        let type_tag = event.type_.to_canonical_string(true);
+
+        tracing::warn!(
+            pipeline,
+            transaction_digest = %provenance.transaction_digest,
+            event_index,
+            type_tag = %type_tag,
+            checkpoint = provenance.checkpoint_sequence_number,
+            "Dead-lettering undecodable event: {error:#}"
+        );
+
+        Ok(NewDeadLetter {
+            pipeline: pipeline.to_string(),
+            kind: "EVENT".to_string(),
+            item_key: format!("{}:{}", provenance.transaction_digest, event_index),
+            type_tag,
+            object_id: None,
+            event_index: Some(event_index as i64),
+            object_state: None,
+            raw_bytes: bcs::to_bytes(event)?,
+            error: format!("{error:#}"),
+            checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+            transaction_digest: provenance.transaction_digest.clone(),
+            object_version: provenance.object_version,
+            package_version: provenance.package_version,
+        })
+    }
+}
+
+impl NewDeadLetter {
+    /// Dead letter for `object`, which decoded but could not be applied or failed to decode
+    /// with `error`
+    pub fn object(
+        pipeline: &str,
+        object: &Object,
+        state: ObjectState,
+        provenance: &Provenance,
+        error: String,
+    ) -> Result<Self> {
+        let object_id = object.id().to_string();
+        let type_tag = object
+            .type_()
+            .map(|type_| type_.to_canonical_string(true))
+            .unwrap_or_default();
+
+        Ok(NewDeadLetter {
+            pipeline: pipeline.to_string(),
+            kind: "OBJECT".to_string(),
+            item_key: object_id.clone(),
+            type_tag,
+            object_id: Some(object_id),
+            event_index: None,
+            object_state: Some(state.as_str().to_string()),
+            raw_bytes: bcs::to_bytes(object)?,
+            error,
+            checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+            transaction_digest: provenance.transaction_digest.clone(),
+            object_version: provenance.object_version,
+            package_version: provenance.package_version,
+        })
+    }
+}
+
+impl DeadLetter {
+    /// Provenance of the checkpoint the dead letter was recorded at
+    pub fn provenance(&self) -> Provenance {
+        Provenance {
+            checkpoint_sequence_number: self.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: self.checkpoint_timestamp_ms,
+            transaction_digest: self.transaction_digest.clone(),
+            object_version: self.object_version,
+            package_version: self.package_version,
+        }
+    }
+
+    pub fn object(&self) -> Result<Object> {
+        Ok(bcs::from_bytes(&self.raw_bytes)?)
+    }
+
+    pub fn event(&self) -> Result<Event> {
+        Ok(bcs::from_bytes(&self.raw_bytes)?)
+    }
+
+    /// Where the letter's object was in its checkpoint
+    pub fn object_state(&self) -> Result<ObjectState> {
+        match self.object_state.as_deref() {
+            Some("LIVE") => Ok(ObjectState::Live),
+            Some("WRAPPED") => Ok(ObjectState::Wrapped),
+            Some("DELETED") => Ok(ObjectState::Deleted),
+            other => bail!("Object dead letter {} has invalid object_state {other:?}", self.id),
+        }
+    }
+}
+
+/// Record dead letters. One already recorded, by a replayed checkpoint or by a retry whose
+/// commit still cannot apply it, is left pending with the latest error.
+pub async fn insert_dead_letters(
+    letters: &[NewDeadLetter],
+    conn: &mut postgres::Connection<'_>,
+) -> Result<usize> {
+    if letters.is_empty() {
+        return Ok(0);
+    }
+
+    Ok(diesel::insert_into(indexer_dead_letters::table)
+        .values(letters)
+        .on_conflict((
+            indexer_dead_letters::pipeline,
+            indexer_dead_letters::checkpoint_sequence_number,
+            indexer_dead_letters::item_key,
+        ))
+        .do_update()
+        .set((
+            indexer_dead_letters::status.eq("PENDING"),
+            indexer_dead_letters::error.eq(excluded(indexer_dead_letters::error)),
+        ))
+        .execute(conn)
+        .await?)
+}
+
+/// A pipeline that can rebuild its values from its own dead letters
+#[async_trait::async_trait]
+pub trait RetryDeadLetters: Processor + Handler<Store = postgres::Db> {
+    /// Re-decode `letter` with the current decoders into values for `commit`
+    fn redecode(&self, letter: &DeadLetter) -> Result<Vec<<Self as Processor>::Value>>;
+
+    /// Whether state newer than `letter` has been indexed since, so applying it would
+    /// roll that state back
+    async fn superseded(&self, _letter: &DeadLetter, _conn: &mut postgres::Connection<'_>) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+/// Outcome of a dead letter retry run for one pipeline
+#[derive(Debug, Default)]
+pub struct RetrySummary {
+    pub resolved: usize,
+    pub superseded: usize,
+    pub failed: usize,
+}
+
+/// Retry every pending dead letter of `handler`'s pipeline, oldest checkpoint first
+pub async fn retry_dead_letters<H: RetryDeadLetters>(handler: &H, db: &Db) -> Result<RetrySummary> {
+    let letters: Vec<DeadLetter> = indexer_dead_letters::table
+        .filter(indexer_dead_letters::pipeline.eq(H::NAME))
+        .filter(indexer_dead_letters::status.eq("PENDING"))
+        .order((indexer_dead_letters::checkpoint_sequence_number, indexer_dead_letters::id))
+        .select(DeadLetter::as_select())
+        .load(&mut db.connect().await?)
+        .await?;
+
+    let mut summary = RetrySummary::default();
+
+    for letter in &letters {
+        // The commit and the status update apply together or not at all, and the change
+        // log lock and notifications behave as they do under the live pipeline
+        let status = db
+            .transaction(|conn| async move { retry_letter(handler, letter, conn).await }.scope_boxed())
+            .await?;
+
+        match status {
+            "RESOLVED" => summary.resolved += 1,
+            "SUPERSEDED" => summary.superseded += 1,
+            _ => summary.failed += 1,
+        }
+    }
+
+    tracing::info!(
+        pipeline = H::NAME,
+        resolved = summary.resolved,
+        superseded = summary.superseded,
+        failed = summary.failed,
+        "Retried dead letters"
+    );
+
+    Ok(summary)
+}
+
+/// Retry one letter, returning its new status: RESOLVED, SUPERSEDED, or PENDING if it
+/// still fails to decode or cannot be applied yet
+async fn retry_letter<H: RetryDeadLetters>(
+    handler: &H,
+    letter: &DeadLetter,
+    conn: &mut postgres::Connection<'_>,
+) -> Result<&'static str> {
+    let values = match handler.redecode(letter) {
+        Ok(values) => values,
+        Err(e) => {
+            diesel::update(indexer_dead_letters::table.find(letter.id))
+                .set((
+                    indexer_dead_letters::error.eq(format!("{e:#}")),
+                    indexer_dead_letters::retry_count.eq(indexer_dead_letters::retry_count + 1),
+                    indexer_dead_letters::last_retry_at.eq(diesel::dsl::now.nullable()),
+                ))
+                .execute(conn)
+                .await?;
+            return Ok("PENDING");
+        }
+    };
+
+    // A later failure of the same object carries newer state than this one
+    let newer_letters: i64 = indexer_dead_letters::table
+        .filter(indexer_dead_letters::pipeline.eq(&letter.pipeline))
+        .filter(indexer_dead_letters::item_key.eq(&letter.item_key))
+        .filter(indexer_dead_letters::checkpoint_sequence_number.gt(letter.checkpoint_sequence_number))
+        .count()
+        .get_result(conn)
+        .await?;
+
+    let status = if newer_letters > 0 || handler.superseded(letter, conn).await? {
+        "SUPERSEDED"
+    } else {
+        diesel::update(indexer_dead_letters::table.find(letter.id))
+            .set(indexer_dead_letters::status.eq("RESOLVED"))
+            .execute(conn)
+            .await?;
+
+        // Folded through `batch`, exactly as the live pipeline would. A commit that still
+        // cannot apply the letter (its room is not indexed yet, say) records it again,
+        // which leaves it pending
+        let mut batch = H::Batch::default();
+        H::batch(&mut batch, values);
+        H::commit(&batch, conn).await?;
+
+        let status: String = indexer_dead_letters::table
+            .find(letter.id)
+            .select(indexer_dead_letters::status)
+            .get_result(conn)
+            .await?;
+        if status == "PENDING" { "PENDING" } else { "RESOLVED" }
+    };
+
+    diesel::update(indexer_dead_letters::table.find(letter.id))
+        .set((
+            indexer_dead_letters::status.eq(status),
+            indexer_dead_letters::retry_count.eq(indexer_dead_letters::retry_count + 1),
+            indexer_dead_letters::last_retry_at.eq(diesel::dsl::now.nullable()),
+            indexer_dead_letters::resolved_at.eq((status != "PENDING").then(|| chrono::Utc::now().naive_utc())),
+        ))
+        .execute(conn)
+        .await?;
+
+    Ok(status)
+}
+
+#[cfg(test)]
+impl NewDeadLetter {
+    /// The row `insert_dead_letters` would write, as read back
+    pub(crate) fn recorded(self, id: i64) -> DeadLetter {
+        DeadLetter {
+            id,
+            pipeline: self.pipeline,
+            kind: self.kind,
+            item_key: self.item_key,
+            type_tag: self.type_tag,
+            object_id: self.object_id,
+            event_index: self.event_index,
+            object_state: self.object_state,
+            raw_bytes: self.raw_bytes,
+            error: self.error,
+            status: "PENDING".to_string(),
+            retry_count: 0,
+            last_retry_at: None,
+            resolved_at: None,
+            checkpoint_sequence_number: self.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: self.checkpoint_timestamp_ms,
+            transaction_digest: self.transaction_digest,
+            object_version: self.object_version,
+            package_version: self.package_version,
+            created_at: chrono::NaiveDateTime::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::anyhow;
+    use move_core_types::identifier::Identifier;
+    use sui_indexer_alt_framework::types::base_types::{ObjectID, SuiAddress};
+    use sui_indexer_alt_framework::types::parse_sui_struct_tag;
+
+    fn provenance() -> Provenance {
+        Provenance {
+            checkpoint_sequence_number: 42,
+            checkpoint_timestamp_ms: 42_000,
+            transaction_digest: "tx42".to_string(),
+            object_version: 9,
+            package_version: Some(1),
+        }
+    }
+
+    fn object() -> Object {
+        Object::with_id_owner_for_testing(ObjectID::from_hex_literal("0x5").unwrap(), SuiAddress::ZERO)
+    }
+
+    fn event() -> Event {
+        Event {
+            package_id: ObjectID::from_hex_literal("0xc90c").unwrap(),
+            transaction_module: Identifier::new("sealmeet").unwrap(),
+            sender: SuiAddress::ZERO,
+            type_: parse_sui_struct_tag("0xc90c::sealmeet::RoomStarted").unwrap(),
+            contents: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn test_fail_policy_returns_decode_error() {
+        let policy = DecodeErrorPolicy::Fail;
+
+        let error = policy
+            .object_letter("room_processor", &object(), ObjectState::Live, &provenance(), anyhow!("bad room"))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "bad room");
+
+        let error = policy
+            .event_letter("event_processor", &event(), 3, &provenance(), anyhow!("bad event"))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "bad event");
+    }
+
+    #[test]
+    fn test_object_letter_keeps_object_and_state() {
+        let letter = DecodeErrorPolicy::DeadLetter
+            .object_letter("room_processor", &object(), ObjectState::Deleted, &provenance(), anyhow!("bad cap"))
+            .unwrap();
+
+        // Replays of the checkpoint collide on (pipeline, checkpoint, item_key)
+        assert_eq!(letter.kind, "OBJECT");
+        assert_eq!(letter.item_key, object().id().to_string());
+        assert_eq!(letter.object_state.as_deref(), Some("DELETED"));
+        assert_eq!(letter.error, "bad cap");
+
+        let recorded = letter.recorded(1);
+        assert_eq!(recorded.object().unwrap().id(), object().id());
+        assert_eq!(recorded.object_state().unwrap(), ObjectState::Deleted);
+    }
+
+    #[test]
+    fn test_event_letter_keyed_by_transaction_and_index() {
+        let letter = DecodeErrorPolicy::DeadLetter
+            .event_letter("event_processor", &event(), 3, &provenance(), anyhow!("bad event"))
+            .unwrap();
+
+        assert_eq!(letter.kind, "EVENT");
+        assert_eq!(letter.item_key, "tx42:3");
+        assert_eq!(letter.event_index, Some(3));
+        assert_eq!(letter.object_state, None);
+        assert_eq!(letter.recorded(1).event().unwrap().contents, vec![1, 2, 3]);
+    }
+}
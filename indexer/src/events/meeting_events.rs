@@ -1,4 +1,8 @@
 // Meeting Room Events
+//
+// Events are BCS-decoded through the generated `sealmeet` bindings and converted here.
+// The conversions destructure every binding field, so a field added to or removed from
+// the Move event fails to compile instead of silently misreading bytes.
 
 use serde::{Deserialize, Serialize};
 use sui_indexer_alt_framework::{
@@ -6,15 +10,34 @@ use sui_indexer_alt_framework::{
     types::base_types::ObjectID,
 };
 
+use crate::models::sealmeet::sealmeet as bindings;
+use crate::utils::address_to_sui_address;
+
+fn object_id(id: move_types::ObjectId) -> ObjectID {
+    ObjectID::from(move_types::Address::from(id.0))
+}
+
 /// RoomCreated event - emitted when a new meeting room is created
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoomCreated {
     pub room_id: ObjectID,
     pub host: SuiAddress,
-    pub title: Vec<u8>,
+    pub title: String,
     pub created_at: u64,
 }
 
+impl From<bindings::RoomCreated> for RoomCreated {
+    fn from(event: bindings::RoomCreated) -> Self {
+        let bindings::RoomCreated { room_id, host, title, created_at } = event;
+        Self {
+            room_id: object_id(room_id),
+            host: address_to_sui_address(&host),
+            title,
+            created_at,
+        }
+    }
+}
+
 /// RoomStarted event - emitted when a meeting starts
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoomStarted {
@@ -22,6 +45,16 @@ pub struct RoomStarted {
     pub started_at: u64,
 }
 
+impl From<bindings::RoomStarted> for RoomStarted {
+    fn from(event: bindings::RoomStarted) -> Self {
+        let bindings::RoomStarted { room_id, started_at } = event;
+        Self {
+            room_id: object_id(room_id),
+            started_at,
+        }
+    }
+}
+
 /// RoomEnded event - emitted when a meeting ends
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoomEnded {
@@ -29,6 +62,16 @@ pub struct RoomEnded {
     pub ended_at: u64,
 }
 
+impl From<bindings::RoomEnded> for RoomEnded {
+    fn from(event: bindings::RoomEnded) -> Self {
+        let bindings::RoomEnded { room_id, ended_at } = event;
+        Self {
+            room_id: object_id(room_id),
+            ended_at,
+        }
+    }
+}
+
 /// GuestApproved event - emitted when a guest is approved
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GuestApproved {
@@ -37,6 +80,17 @@ pub struct GuestApproved {
     pub approved_by: SuiAddress,
 }
 
+impl From<bindings::GuestApproved> for GuestApproved {
+    fn from(event: bindings::GuestApproved) -> Self {
+        let bindings::GuestApproved { room_id, guest, approved_by } = event;
+        Self {
+            room_id: object_id(room_id),
+            guest: address_to_sui_address(&guest),
+            approved_by: address_to_sui_address(&approved_by),
+        }
+    }
+}
+
 /// GuestRevoked event - emitted when a guest approval is revoked
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GuestRevoked {
@@ -45,17 +99,51 @@ pub struct GuestRevoked {
     pub revoked_by: SuiAddress,
 }
 
+impl From<bindings::GuestRevoked> for GuestRevoked {
+    fn from(event: bindings::GuestRevoked) -> Self {
+        let bindings::GuestRevoked { room_id, guest, revoked_by } = event;
+        Self {
+            room_id: object_id(room_id),
+            guest: address_to_sui_address(&guest),
+            revoked_by: address_to_sui_address(&revoked_by),
+        }
+    }
+}
+
 /// HostCapGranted event - emitted when a new host capability is granted
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HostCapGranted {
     pub room_id: ObjectID,
     pub new_host: SuiAddress,
+    pub admin_cap_object_id: ObjectID,
     pub granted_by: SuiAddress,
 }
 
+impl From<bindings::HostCapGranted> for HostCapGranted {
+    fn from(event: bindings::HostCapGranted) -> Self {
+        let bindings::HostCapGranted { room_id, new_host, admin_cap_object_id, granted_by } = event;
+        Self {
+            room_id: object_id(room_id),
+            new_host: address_to_sui_address(&new_host),
+            admin_cap_object_id: object_id(admin_cap_object_id),
+            granted_by: address_to_sui_address(&granted_by),
+        }
+    }
+}
+
 /// MetadataUpdated event - emitted when room metadata is updated
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetadataUpdated {
     pub room_id: ObjectID,
     pub updated_by: SuiAddress,
 }
+
+impl From<bindings::MetadataUpdated> for MetadataUpdated {
+    fn from(event: bindings::MetadataUpdated) -> Self {
+        let bindings::MetadataUpdated { room_id, updated_by } = event;
+        Self {
+            room_id: object_id(room_id),
+            updated_by: address_to_sui_address(&updated_by),
+        }
+    }
+}
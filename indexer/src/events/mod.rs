@@ -1,11 +1,13 @@
 // Meeting Room events module
 
 pub mod meeting_events;
+pub mod registry;
 
 pub use meeting_events::*;
+pub use registry::*;
 
 use anyhow::{Result, Context};
-use sui_indexer_alt_framework::types::event::Event;
+use sui_indexer_alt_framework::types::base_types::ObjectID;
 
 /// Unified enum for all meeting room events
 #[derive(Debug, Clone)]
@@ -20,51 +22,32 @@ pub enum MeetingRoomEvent {
 }
 
 impl MeetingRoomEvent {
-    /// Parse a Sui event into a strongly-typed MeetingRoomEvent
-    pub fn from_sui_event(event: &Event) -> Result<Option<Self>> {
-        let event_type = event.type_.name.as_str();
+    /// Get the room this event belongs to
+    pub fn room_id(&self) -> ObjectID {
+        match self {
+            Self::RoomCreated(e) => e.room_id,
+            Self::RoomStarted(e) => e.room_id,
+            Self::RoomEnded(e) => e.room_id,
+            Self::GuestApproved(e) => e.room_id,
+            Self::GuestRevoked(e) => e.room_id,
+            Self::HostCapGranted(e) => e.room_id,
+            Self::MetadataUpdated(e) => e.room_id,
+        }
+    }
 
-        // Use BCS deserialization for type-safe parsing
-        let parsed = match event_type {
-            "RoomCreated" => {
-                let data: RoomCreated = bcs::from_bytes(&event.contents)
-                    .context("Failed to deserialize RoomCreated event")?;
-                Self::RoomCreated(data)
-            }
-            "RoomStarted" => {
-                let data: RoomStarted = bcs::from_bytes(&event.contents)
-                    .context("Failed to deserialize RoomStarted event")?;
-                Self::RoomStarted(data)
-            }
-            "RoomEnded" => {
-                let data: RoomEnded = bcs::from_bytes(&event.contents)
-                    .context("Failed to deserialize RoomEnded event")?;
-                Self::RoomEnded(data)
-            }
-            "GuestApproved" => {
-                let data: GuestApproved = bcs::from_bytes(&event.contents)
-                    .context("Failed to deserialize GuestApproved event")?;
-                Self::GuestApproved(data)
-            }
-            "GuestRevoked" => {
-                let data: GuestRevoked = bcs::from_bytes(&event.contents)
-                    .context("Failed to deserialize GuestRevoked event")?;
-                Self::GuestRevoked(data)
-            }
-            "HostCapGranted" => {
-                let data: HostCapGranted = bcs::from_bytes(&event.contents)
-                    .context("Failed to deserialize HostCapGranted event")?;
-                Self::HostCapGranted(data)
-            }
-            "MetadataUpdated" => {
-                let data: MetadataUpdated = bcs::from_bytes(&event.contents)
-                    .context("Failed to deserialize MetadataUpdated event")?;
-                Self::MetadataUpdated(data)
-            }
-            _ => return Ok(None), // Unknown event type
+    /// Serialize the decoded event fields to JSON for storage
+    pub fn to_json(&self) -> Result<serde_json::Value> {
+        let value = match self {
+            Self::RoomCreated(e) => serde_json::to_value(e),
+            Self::RoomStarted(e) => serde_json::to_value(e),
+            Self::RoomEnded(e) => serde_json::to_value(e),
+            Self::GuestApproved(e) => serde_json::to_value(e),
+            Self::GuestRevoked(e) => serde_json::to_value(e),
+            Self::HostCapGranted(e) => serde_json::to_value(e),
+            Self::MetadataUpdated(e) => serde_json::to_value(e),
         };
 
-        Ok(Some(parsed))
+        value.context("Failed to serialize event to JSON")
     }
 
     /// Get the event type name as a string
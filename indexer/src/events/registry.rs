@@ -0,0 +1,99 @@
+// Event registry - maps full event type tags to their decoders
+
+use std::collections::HashMap;
+use anyhow::{Context, Result};
+use move_core_types::language_storage::StructTag;
+use serde::de::DeserializeOwned;
+use sui_indexer_alt_framework::types::event::Event;
+
+use super::*;
+use crate::models::sealmeet::sealmeet as bindings;
+use crate::utils::SuimeetPackages;
+
+/// Module that defines the SuiMeet events
+const SEALMEET_MODULE: &str = "sealmeet";
+
+type DecodeEventFn = fn(&[u8]) -> Result<MeetingRoomEvent>;
+
+/// Decode with the generated binding `B`, then convert to the indexer's event struct
+fn decode<B: DeserializeOwned, T: From<B>>(
+    contents: &[u8],
+    wrap: fn(T) -> MeetingRoomEvent,
+) -> Result<MeetingRoomEvent> {
+    Ok(wrap(T::from(bcs::from_bytes::<B>(contents)?)))
+}
+
+/// Every SuiMeet event, by module and struct name
+pub(crate) const EVENTS: &[(&str, &str, DecodeEventFn)] = &[
+    (SEALMEET_MODULE, "RoomCreated", |c| decode::<bindings::RoomCreated, _>(c, MeetingRoomEvent::RoomCreated)),
+    (SEALMEET_MODULE, "RoomStarted", |c| decode::<bindings::RoomStarted, _>(c, MeetingRoomEvent::RoomStarted)),
+    (SEALMEET_MODULE, "RoomEnded", |c| decode::<bindings::RoomEnded, _>(c, MeetingRoomEvent::RoomEnded)),
+    (SEALMEET_MODULE, "GuestApproved", |c| decode::<bindings::GuestApproved, _>(c, MeetingRoomEvent::GuestApproved)),
+    (SEALMEET_MODULE, "GuestRevoked", |c| decode::<bindings::GuestRevoked, _>(c, MeetingRoomEvent::GuestRevoked)),
+    (SEALMEET_MODULE, "HostCapGranted", |c| decode::<bindings::HostCapGranted, _>(c, MeetingRoomEvent::HostCapGranted)),
+    (SEALMEET_MODULE, "MetadataUpdated", |c| decode::<bindings::MetadataUpdated, _>(c, MeetingRoomEvent::MetadataUpdated)),
+];
+
+/// Decoders for SuiMeet events, keyed on the full type tag under every configured package id
+///
+/// Only an exact (address, module, name) match is decoded, so a same-named struct in another
+/// module or package is ignored rather than misread.
+#[derive(Clone)]
+pub struct EventRegistry {
+    decoders: HashMap<StructTag, DecodeEventFn>,
+}
+
+impl EventRegistry {
+    pub fn new(packages: &SuimeetPackages) -> Result<Self> {
+        let mut decoders = HashMap::new();
+
+        // An event introduced by an upgrade carries that upgrade's address, so register
+        // every event under every known version
+        for package_id in packages.ids() {
+            for (module, name, decode) in EVENTS {
+                let type_ = StructTag {
+                    address: package_id.into(),
+                    module: module.parse()?,
+                    name: name.parse()?,
+                    type_params: vec![],
+                };
+                decoders.insert(type_, *decode);
+            }
+        }
+
+        Ok(Self { decoders })
+    }
+
+    /// Whether `type_` is a registered SuiMeet event
+    pub fn contains(&self, type_: &StructTag) -> bool {
+        self.decoders.contains_key(type_)
+    }
+
+    /// Decode `event`, or `None` if its type is not a registered SuiMeet event
+    pub fn decode(&self, event: &Event) -> Result<Option<MeetingRoomEvent>> {
+        let Some(decode) = self.decoders.get(&event.type_) else {
+            return Ok(None);
+        };
+
+        decode(&event.contents)
+            .with_context(|| format!("Failed to deserialize {} event", event.type_))
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_full_type_tag_only() {
+        let packages = SuimeetPackages::new("0x1234", &["0x5678".to_string()]).unwrap();
+        let registry = EventRegistry::new(&packages).unwrap();
+
+        let tag = |s: &str| -> StructTag { s.parse().unwrap() };
+        assert!(registry.contains(&tag("0x1234::sealmeet::RoomCreated")));
+        assert!(registry.contains(&tag("0x5678::sealmeet::GuestApproved")));
+        assert!(!registry.contains(&tag("0x1234::other_module::RoomCreated")));
+        assert!(!registry.contains(&tag("0x9999::sealmeet::RoomCreated")));
+    }
+}
@@ -0,0 +1,156 @@
+// Checks the Rust structs the indexer decodes against the Move sources in `sealmeet/sources`
+//
+// The bindings are generated from the same sources by `build.rs`, so these tests guard the
+// generator and the event registry rather than a pinned on-chain package.
+//
+// For each struct a BCS value is built from the field list in the Move source, one sample
+// element per vector and `Some` for every option, and must decode with no bytes left over.
+// Adding, removing, reordering or retyping a Move field makes these tests fail.
+
+#[path = "../build/move_sources.rs"]
+mod move_sources;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+
+use crate::events::EVENTS;
+use crate::models::sealmeet::{seal_approve_whitelist, sealmeet};
+use move_sources::{parse_module, read_move_structs, MoveStruct};
+
+/// Struct name -> (field name, field type), in declaration order
+type MoveStructs = HashMap<String, Vec<(String, String)>>;
+
+fn sealmeet_structs() -> Vec<MoveStruct> {
+    let sources = Path::new(env!("CARGO_MANIFEST_DIR")).join("../sealmeet/sources");
+    read_move_structs(&sources).expect("sealmeet/sources is readable")
+}
+
+fn fields_by_name(structs: &[MoveStruct]) -> MoveStructs {
+    structs.iter().map(|s| (s.name.clone(), s.fields.clone())).collect()
+}
+
+/// Sample BCS bytes for a value of Move type `type_`
+fn sample_bcs(type_: &str, structs: &MoveStructs, out: &mut Vec<u8>) {
+    let type_ = type_.trim();
+
+    if let Some(inner) = type_.strip_prefix("vector<").and_then(|t| t.strip_suffix('>')) {
+        out.push(1);
+        sample_bcs(inner, structs, out);
+        return;
+    }
+
+    if let Some(inner) = type_.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        out.push(1);
+        sample_bcs(inner, structs, out);
+        return;
+    }
+
+    // Table<K, V> is { id: UID, size: u64 }, regardless of K and V
+    if type_.starts_with("Table<") {
+        out.extend([0u8; 32 + 8]);
+        return;
+    }
+
+    match type_ {
+        "bool" => out.push(1),
+        "u8" => out.push(7),
+        "u16" => out.extend(7u16.to_le_bytes()),
+        "u32" => out.extend(7u32.to_le_bytes()),
+        "u64" => out.extend(7u64.to_le_bytes()),
+        "u128" => out.extend(7u128.to_le_bytes()),
+        "u256" => out.extend([7u8; 32]),
+        "address" | "ID" | "UID" => out.extend([7u8; 32]),
+        "String" => {
+            out.push(3);
+            out.extend(b"abc");
+        }
+        name => {
+            let fields = structs
+                .get(name)
+                .unwrap_or_else(|| panic!("Move type `{name}` is not declared in sealmeet/sources"));
+            for (_, field_type) in fields {
+                sample_bcs(field_type, structs, out);
+            }
+        }
+    }
+}
+
+fn assert_layout<T: DeserializeOwned>(name: &str, structs: &MoveStructs) {
+    let mut bytes = Vec::new();
+    sample_bcs(name, structs, &mut bytes);
+
+    if let Err(e) = bcs::from_bytes::<T>(&bytes) {
+        panic!("{} does not match `{name}` in sealmeet/sources: {e}", std::any::type_name::<T>());
+    }
+}
+
+#[test]
+fn test_object_layouts_match_move_sources() {
+    let structs = fields_by_name(&sealmeet_structs());
+
+    assert_layout::<sealmeet::MeetingRoom>("MeetingRoom", &structs);
+    assert_layout::<sealmeet::HostCap>("HostCap", &structs);
+    assert_layout::<sealmeet::MeetingMetadata>("MeetingMetadata", &structs);
+    assert_layout::<sealmeet::RoomRegistry>("RoomRegistry", &structs);
+    assert_layout::<seal_approve_whitelist::SealApproveWhitelist>("SealApproveWhitelist", &structs);
+}
+
+#[test]
+fn test_event_layouts_match_move_sources() {
+    let sources = sealmeet_structs();
+    let structs = fields_by_name(&sources);
+
+    // Events are the `copy, drop` structs without `store`
+    let mut move_events: Vec<&str> = sources
+        .iter()
+        .filter(|s| s.has("copy") && !s.has("store"))
+        .map(|s| s.name.as_str())
+        .collect();
+    move_events.sort();
+
+    let mut registered: Vec<&str> = EVENTS.iter().map(|(_, name, _)| *name).collect();
+    registered.sort();
+    assert_eq!(move_events, registered, "every Move event must be registered in events::registry");
+
+    // Each registered decoder accepts a value laid out from the Move source
+    for (_, name, decode) in EVENTS {
+        let mut bytes = Vec::new();
+        sample_bcs(name, &structs, &mut bytes);
+        if let Err(e) = decode(&bytes) {
+            panic!("{name} decoder does not match sealmeet/sources: {e:#}");
+        }
+    }
+}
+
+#[test]
+fn test_move_parser_skips_comments_and_strings() {
+    let source = r#"
+        module a::m;
+        /* public struct Commented { x: u8 } /* nested } */ */
+        const NAME: vector<u8> = b"struct Quoted { }";
+        public struct Wrapper<phantom T: store>(u64, vector<sui::object::ID>) has copy, drop;
+        public struct Room has key { // a } in a comment
+            id: UID,
+            by_host: sui::table::Table<address, vector<ID>>,
+        }
+    "#;
+    let structs = parse_module(source).unwrap();
+
+    let parsed: Vec<(&str, Vec<(&str, &str)>)> = structs
+        .iter()
+        .map(|s| (s.name.as_str(), s.fields.iter().map(|(f, t)| (f.as_str(), t.as_str())).collect()))
+        .collect();
+    assert_eq!(
+        parsed,
+        vec![
+            ("Wrapper", vec![("pos0", "u64"), ("pos1", "vector<ID>")]),
+            ("Room", vec![("id", "UID"), ("by_host", "Table<address, vector<ID>>")]),
+        ]
+    );
+    assert!(structs[0].has("drop"));
+
+    let error = parse_module("module a::m; public struct Bad { field }").unwrap_err();
+    assert!(error.contains("m::Bad"), "{error}");
+}
@@ -7,7 +7,14 @@ pub mod db;
 pub mod events;
 pub mod utils;
 pub mod processors;
+pub mod dead_letters;
+pub mod changes;  // Change log streamed by the read API
+pub mod api;  // Read API served by --serve-api
+pub mod webhooks;  // Outbox drained by --deliver-webhooks
 pub mod models;  // Move-binding generated types
 
+#[cfg(test)]
+mod layout_tests;  // Bindings checked against sealmeet/sources
+
 // Embed database migrations into the binary so they run automatically on startup
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
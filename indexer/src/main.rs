@@ -1,18 +1,30 @@
 // SuiMeet Indexer - Meeting Room Event Indexer
 
+use std::net::SocketAddr;
+use std::time::Duration;
+
 use clap::{Parser, Args as ClapArgs};
 use sui_indexer_alt_framework::{
     cluster::{self, IndexerClusterBuilder},
     pipeline::sequential::SequentialConfig,
     ingestion::IngestionConfig,
+    postgres::{Db, DbArgs},
     Result,
 };
 use url::Url;
 use suimeet_indexer::{
-    processors::{RoomProcessor, MetadataProcessor},
+    api,
+    changes::prune_changes_periodically,
+    dead_letters::{retry_dead_letters, DecodeErrorPolicy},
+    processors::{RoomProcessor, EventProcessor, RegistryProcessor},
+    utils::SuimeetPackages,
+    webhooks::deliver_webhooks,
     MIGRATIONS,
 };
 
+/// How often the change log is pruned while indexing
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
 /// Ingestion configuration arguments
 #[derive(ClapArgs, Debug, Clone)]
 struct IngestionArgs {
@@ -50,10 +62,55 @@ struct Args {
     #[clap(long, env = "DATABASE_URL")]
     database_url: Url,
 
-    /// SuiMeet package ID on Sui
+    /// SuiMeet package ID on Sui (the original publish)
     #[clap(long, env = "SUIMEET_PACKAGE_ID")]
     suimeet_package_id: String,
 
+    /// Package IDs of SuiMeet upgrades, comma separated, in upgrade order
+    #[clap(long, env = "SUIMEET_UPGRADED_PACKAGE_IDS", value_delimiter = ',')]
+    suimeet_upgraded_package_ids: Vec<String>,
+
+    /// What to do with objects and events that fail to decode
+    #[clap(long, env = "ON_DECODE_ERROR", value_enum, default_value_t = DecodeErrorPolicy::DeadLetter)]
+    on_decode_error: DecodeErrorPolicy,
+
+    /// Re-decode pending dead letters with the current decoders, commit the ones that
+    /// now succeed, and exit without indexing
+    #[clap(long)]
+    retry_dead_letters: bool,
+
+    /// Serve the read API over the indexed tables instead of indexing
+    #[clap(long)]
+    serve_api: bool,
+
+    /// Address the read API listens on
+    #[clap(long, env = "API_LISTEN_ADDRESS", default_value = "0.0.0.0:8080")]
+    api_listen_address: SocketAddr,
+
+    /// How often the read API polls for committed changes to stream (ms)
+    #[clap(long, env = "CHANGE_POLL_INTERVAL_MS", default_value = "200")]
+    change_poll_interval_ms: u64,
+
+    /// How long committed changes stay in `room_changes` (hours); 0 keeps them forever
+    #[clap(long, env = "ROOM_CHANGES_RETENTION_HOURS", default_value = "168")]
+    room_changes_retention_hours: u64,
+
+    /// Deliver queued webhooks instead of indexing
+    #[clap(long)]
+    deliver_webhooks: bool,
+
+    /// How often the webhook worker checks for due deliveries once the outbox is drained (ms)
+    #[clap(long, env = "WEBHOOK_POLL_INTERVAL_MS", default_value = "1000")]
+    webhook_poll_interval_ms: u64,
+
+    /// Timeout of one webhook request (ms)
+    #[clap(long, env = "WEBHOOK_TIMEOUT_MS", default_value = "10000")]
+    webhook_timeout_ms: u64,
+
+    /// Attempts before a webhook delivery is marked FAILED
+    #[clap(long, env = "WEBHOOK_MAX_ATTEMPTS", default_value = "12")]
+    webhook_max_attempts: i32,
+
     #[clap(flatten)]
     cluster_args: cluster::Args,
 
@@ -69,10 +126,61 @@ async fn main() -> Result<()> {
     let Args {
         database_url,
         suimeet_package_id,
+        suimeet_upgraded_package_ids,
+        on_decode_error,
+        retry_dead_letters: retry_only,
+        serve_api,
+        api_listen_address,
+        change_poll_interval_ms,
+        room_changes_retention_hours,
+        deliver_webhooks: deliver_only,
+        webhook_poll_interval_ms,
+        webhook_timeout_ms,
+        webhook_max_attempts,
         cluster_args,
         ingestion_args,
     } = Args::parse();
 
+    if serve_api {
+        let db = Db::for_read(database_url, DbArgs::default()).await?;
+        let change_poll_interval = Duration::from_millis(change_poll_interval_ms);
+        return api::serve(db, api_listen_address, change_poll_interval).await;
+    }
+
+    if deliver_only {
+        let db = Db::for_write(database_url, DbArgs::default()).await?;
+        return deliver_webhooks(
+            db,
+            Duration::from_millis(webhook_poll_interval_ms),
+            Duration::from_millis(webhook_timeout_ms),
+            webhook_max_attempts,
+        )
+        .await;
+    }
+
+    let packages = SuimeetPackages::new(&suimeet_package_id, &suimeet_upgraded_package_ids)?;
+
+    let room_processor = RoomProcessor::new(packages.clone(), on_decode_error)?;
+    let event_processor = EventProcessor::new(packages.clone(), on_decode_error)?;
+    let registry_processor = RegistryProcessor::new(packages, on_decode_error)?;
+
+    if retry_only {
+        let db = Db::for_write(database_url, DbArgs::default()).await?;
+
+        retry_dead_letters(&room_processor, &db).await?;
+        retry_dead_letters(&event_processor, &db).await?;
+        retry_dead_letters(&registry_processor, &db).await?;
+
+        return Ok(());
+    }
+
+    // Prune the change log alongside indexing; the API only streams recent changes
+    if room_changes_retention_hours > 0 {
+        let db = Db::for_write(database_url.clone(), DbArgs::default()).await?;
+        let retention = Duration::from_secs(room_changes_retention_hours * 3600);
+        tokio::spawn(prune_changes_periodically(db, retention, PRUNE_INTERVAL));
+    }
+
     // Build and configure the indexer cluster with framework's builder
     let mut indexer = IndexerClusterBuilder::new()
         .with_ingestion_config(IngestionConfig::from(ingestion_args))
@@ -82,18 +190,20 @@ async fn main() -> Result<()> {
         .build()
         .await?;
 
-    // Create and register room processor
-    let room_processor = RoomProcessor::new(suimeet_package_id.clone())?;
-
+    // Register room processor, which also commits room metadata so the two tables share
+    // one watermark and transaction
     indexer
         .sequential_pipeline(room_processor, SequentialConfig::default())
         .await?;
 
-    // Create and register metadata processor
-    let metadata_processor = MetadataProcessor::new(suimeet_package_id)?;
+    // Register event log processor
+    indexer
+        .sequential_pipeline(event_processor, SequentialConfig::default())
+        .await?;
 
+    // Register room registry processor
     indexer
-        .sequential_pipeline(metadata_processor, SequentialConfig::default())
+        .sequential_pipeline(registry_processor, SequentialConfig::default())
         .await?;
 
     // Start the indexer and wait for completion
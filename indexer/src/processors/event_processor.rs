@@ -0,0 +1,192 @@
+// Event Processor - Append-only log of SuiMeet events
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use anyhow::Result;
+use diesel_async::RunQueryDsl;
+use sui_indexer_alt_framework::{
+    pipeline::{Processor, sequential::Handler},
+    postgres,
+    types::event::Event,
+    types::full_checkpoint_content::CheckpointData,
+    FieldCount,
+};
+
+use crate::changes::append_changes;
+use crate::dead_letters::{insert_dead_letters, DecodeErrorPolicy, RetryDeadLetters};
+use crate::events::{EventRegistry, MeetingRoomEvent};
+use crate::utils::{CheckpointProvenance, Provenance, SuimeetPackages};
+use crate::webhooks::{self, enqueue_deliveries};
+use crate::db::max_insert_rows;
+use crate::db::models::{DeadLetter, NewDeadLetter, NewRoomChange, NewRoomEvent};
+use crate::db::schema::room_events;
+
+/// Enum representing the event log rows to persist
+#[derive(Debug, Clone)]
+pub enum ProcessedValue {
+    /// Append a decoded event
+    Event(NewRoomEvent),
+    /// Record an event that failed to decode
+    DeadLetter(NewDeadLetter),
+}
+
+impl FieldCount for ProcessedValue {
+    const FIELD_COUNT: usize = 13;
+}
+
+pub struct EventProcessor {
+    packages: SuimeetPackages,
+    decode_error_policy: DecodeErrorPolicy,
+    events: EventRegistry,
+}
+
+impl EventProcessor {
+    pub fn new(packages: SuimeetPackages, decode_error_policy: DecodeErrorPolicy) -> Result<Self> {
+        let events = EventRegistry::new(&packages)?;
+
+        Ok(Self {
+            packages,
+            decode_error_policy,
+            events,
+        })
+    }
+
+    /// Log row for a decoded event
+    fn room_event(
+        &self,
+        parsed: MeetingRoomEvent,
+        event: &Event,
+        event_index: usize,
+        provenance: &Provenance,
+    ) -> Result<NewRoomEvent> {
+        Ok(NewRoomEvent {
+            checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+            transaction_digest: provenance.transaction_digest.clone(),
+            event_index: event_index as i64,
+            event_type: parsed.event_type().to_string(),
+            room_id: parsed.room_id().to_string(),
+            sender: event.sender.to_string(),
+            package_id: event.package_id.to_string(),
+            data: parsed.to_json()?,
+            package_version: self.packages.version_of(&event.package_id),
+        })
+    }
+}
+
+impl Processor for EventProcessor {
+    const NAME: &'static str = "event_processor";
+    type Value = ProcessedValue;
+
+    fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
+        let provenance = CheckpointProvenance::new(checkpoint, &self.packages);
+
+        let mut values = Vec::new();
+
+        for tx in &checkpoint.transactions {
+            let Some(tx_events) = &tx.events else {
+                continue;
+            };
+
+            let tx_provenance = provenance.for_transaction(tx);
+
+            for (event_index, event) in tx_events.data.iter().enumerate() {
+                // Event types keep the address of the package version that defined them,
+                // while `event.package_id` is whichever version's code emitted them
+                let parsed = match self.events.decode(event) {
+                    Ok(Some(parsed)) => parsed,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        values.push(ProcessedValue::DeadLetter(self.decode_error_policy.event_letter(
+                            Self::NAME,
+                            event,
+                            event_index,
+                            &tx_provenance,
+                            e,
+                        )?));
+                        continue;
+                    }
+                };
+
+                values.push(ProcessedValue::Event(self.room_event(parsed, event, event_index, &tx_provenance)?));
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler for EventProcessor {
+    type Store = postgres::Db;
+    type Batch = Vec<ProcessedValue>;
+
+    fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>) {
+        batch.extend(values);
+    }
+
+    async fn commit<'a>(batch: &Self::Batch, conn: &mut postgres::Connection<'a>) -> Result<usize> {
+        let mut total_affected = 0;
+
+        let mut events = Vec::new();
+        let mut dead_letters = Vec::new();
+
+        for value in batch {
+            match value {
+                ProcessedValue::Event(event) => events.push(event.clone()),
+                ProcessedValue::DeadLetter(letter) => dead_letters.push(letter.clone()),
+            }
+        }
+
+        // Events are immutable, so a replayed checkpoint simply skips rows it already wrote,
+        // and only the rows written now go to the change log and the webhook outbox
+        let mut changes = Vec::new();
+        let mut webhook_events = Vec::new();
+        for chunk in events.chunks(max_insert_rows::<NewRoomEvent>()) {
+            let inserted: HashSet<(String, i64)> = diesel::insert_into(room_events::table)
+                .values(chunk)
+                .on_conflict((room_events::transaction_digest, room_events::event_index))
+                .do_nothing()
+                .returning((room_events::transaction_digest, room_events::event_index))
+                .get_results(conn)
+                .await?
+                .into_iter()
+                .collect();
+            total_affected += inserted.len();
+
+            for event in chunk {
+                let key = (event.transaction_digest.clone(), event.event_index);
+                if !inserted.contains(&key) {
+                    continue;
+                }
+
+                changes.push(NewRoomChange::event(event));
+                if webhooks::EVENT_TYPES.contains(&event.event_type.as_str()) {
+                    webhook_events.push(key);
+                }
+            }
+        }
+
+        total_affected += enqueue_deliveries(webhook_events, conn).await?;
+        total_affected += insert_dead_letters(&dead_letters, conn).await?;
+
+        // Last, holding the change log lock only until the commit
+        total_affected += append_changes(&changes, conn).await?;
+
+        Ok(total_affected)
+    }
+}
+
+#[async_trait::async_trait]
+impl RetryDeadLetters for EventProcessor {
+    fn redecode(&self, letter: &DeadLetter) -> Result<Vec<ProcessedValue>> {
+        let event = letter.event()?;
+        let event_index = letter.event_index.unwrap_or_default() as usize;
+
+        let Some(parsed) = self.events.decode(&event)? else {
+            return Ok(vec![]);
+        };
+
+        Ok(vec![ProcessedValue::Event(self.room_event(parsed, &event, event_index, &letter.provenance())?)])
+    }
+}
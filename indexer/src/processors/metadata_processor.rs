@@ -1,98 +1,313 @@
 // Metadata Processor - Handles MeetingMetadata dynamic fields
-
-use std::sync::Arc;
+//
+// Part of the room pipeline rather than a pipeline of its own: `room_metadata` has a
+// foreign key to `meeting_rooms`, so metadata is committed in the same transaction as,
+// and after, the rooms of the same checkpoints. A separate pipeline with its own
+// watermark could write metadata for a room that was not committed yet, or one that was
+// already deleted.
+
+use std::collections::{BTreeMap, HashSet};
 use anyhow::Result;
 use diesel::prelude::*;
+use diesel::upsert::excluded;
 use diesel_async::RunQueryDsl;
 use sui_indexer_alt_framework::{
-    pipeline::{Processor, sequential::Handler},
+    pipeline::Processor,
     postgres,
-    types::full_checkpoint_content::CheckpointData,
     types::base_types::ObjectID,
-    types::parse_sui_struct_tag,
-    FieldCount,
+    types::object::Object,
 };
+use serde_json::json;
 use move_core_types::language_storage::StructTag;
 use sui_types::object::Owner;
 
-use crate::utils::{checkpoint_input_objects, checkpoint_output_objects, extract_meeting_metadata};
-use crate::db::models::NewRoomMetadata;
-use crate::db::schema::room_metadata;
+use crate::utils::{
+    extract_meeting_metadata, CheckpointProvenance, ParsedMeetingMetadata, Provenance, SuimeetPackages,
+};
+use crate::changes::{METADATA_DELETED, METADATA_UPDATED, ROOM_DELETED};
+use crate::dead_letters::{insert_dead_letters, DecodeErrorPolicy, ObjectState};
+use crate::db::max_insert_rows;
+use crate::db::models::{DeadLetter, NewDeadLetter, NewRoomChange, NewRoomMetadata, NewRoomMetadataVersion};
+use crate::db::schema::{meeting_rooms, room_changes, room_metadata, room_metadata_history};
+use super::room_processor::{ProcessedValue, RoomProcessor};
+
+/// Error recorded for metadata whose room has no `meeting_rooms` row
+const ROOM_NOT_INDEXED: &str = "room not indexed";
 
-/// Enum representing the metadata data to persist
+/// Metadata change to persist
 #[derive(Debug, Clone)]
-pub enum ProcessedValue {
+pub enum MetadataValue {
     /// Upsert metadata
-    MetadataUpsert {
+    Upsert {
         room_id: String,
         dynamic_field_id: String,
         df_version: i64,
         language: String,
         timezone: String,
         recording_blob_id: Option<bigdecimal::BigDecimal>,
+        provenance: Provenance,
+        /// The dynamic field, dead-lettered if its room is not indexed
+        object: Object,
+    },
+    /// Delete metadata (when dynamic field is removed without a replacement)
+    Delete {
+        room_id: String,
+        dynamic_field_id: String,
+        provenance: Provenance,
     },
-    /// Delete metadata (when dynamic field is removed)
-    MetadataDelete(String), // room_id
 }
 
-impl FieldCount for ProcessedValue {
-    const FIELD_COUNT: usize = 6;
+/// Metadata values folded, in checkpoint order, into the final metadata of each room
+#[derive(Debug, Default)]
+pub struct MetadataBatch {
+    /// Dynamic fields removed in the batch, with their room, deleted before any upsert is
+    /// written
+    deleted_fields: BTreeMap<String, (String, Provenance)>,
+    /// Last metadata of each room, with the dynamic field it came from
+    metadata: BTreeMap<String, (NewRoomMetadata, Object)>,
+    /// Every version seen, for the history table
+    versions: Vec<NewRoomMetadataVersion>,
+}
+
+impl MetadataBatch {
+    pub fn push(&mut self, value: MetadataValue) {
+        match value {
+            MetadataValue::Upsert {
+                room_id,
+                dynamic_field_id,
+                df_version,
+                language,
+                timezone,
+                recording_blob_id,
+                provenance,
+                object,
+            } => {
+                self.versions.push(NewRoomMetadataVersion {
+                    room_id: room_id.clone(),
+                    dynamic_field_id: dynamic_field_id.clone(),
+                    df_version,
+                    language: language.clone(),
+                    timezone: timezone.clone(),
+                    recording_blob_id: recording_blob_id.clone(),
+                    checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                    checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+                    transaction_digest: provenance.transaction_digest.clone(),
+                });
+                self.metadata.insert(room_id.clone(), (NewRoomMetadata {
+                    room_id,
+                    dynamic_field_id,
+                    df_version,
+                    language,
+                    timezone,
+                    recording_blob_id,
+                    checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                    checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+                    transaction_digest: provenance.transaction_digest,
+                    object_version: provenance.object_version,
+                    package_version: provenance.package_version,
+                }, object));
+            }
+            MetadataValue::Delete { room_id, dynamic_field_id, provenance } => {
+                // Only drop a pending upsert of this very field; the room may have a newer one
+                if self.metadata.get(&room_id).is_some_and(|(m, _)| m.dynamic_field_id == dynamic_field_id) {
+                    self.metadata.remove(&room_id);
+                }
+                self.deleted_fields.insert(dynamic_field_id, (room_id, provenance));
+            }
+        }
+    }
+
+    /// The room was deleted: the cascade removes its metadata, so drop what is pending.
+    /// History rows have no foreign key and are kept.
+    pub fn drop_room(&mut self, room_id: &str) {
+        self.metadata.remove(room_id);
+    }
+
+    /// Change log entries for the folded metadata, deletions first as they are written.
+    /// Upserts are only logged for `indexed_rooms`, the rooms they were written to
+    fn changes(&self, indexed_rooms: &HashSet<String>) -> Vec<NewRoomChange> {
+        let deleted = self.deleted_fields.iter().map(|(dynamic_field_id, (room_id, provenance))| {
+            NewRoomChange::new(
+                room_id,
+                METADATA_DELETED,
+                vec![],
+                json!({ "dynamic_field_id": dynamic_field_id }),
+                provenance,
+            )
+        });
+
+        let updated = self.upserts(indexed_rooms).map(|metadata| NewRoomChange {
+            room_id: metadata.room_id.clone(),
+            change_kind: METADATA_UPDATED.to_string(),
+            addresses: vec![],
+            data: json!({
+                "dynamic_field_id": metadata.dynamic_field_id,
+                "language": metadata.language,
+                "timezone": metadata.timezone,
+                "recording_blob_id": metadata.recording_blob_id.as_ref().map(|id| id.to_string()),
+            }),
+            checkpoint_sequence_number: metadata.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: metadata.checkpoint_timestamp_ms,
+            transaction_digest: metadata.transaction_digest.clone(),
+        });
+
+        deleted.chain(updated).collect()
+    }
+
+    fn upserts<'a>(&'a self, indexed_rooms: &'a HashSet<String>) -> impl Iterator<Item = &'a NewRoomMetadata> {
+        self.metadata
+            .values()
+            .map(|(metadata, _)| metadata)
+            .filter(|metadata| indexed_rooms.contains(&metadata.room_id))
+    }
+
+    /// Write the folded metadata and add its change log entries to `changes`. Must run
+    /// after the batch's rooms are written
+    pub async fn commit(&self, conn: &mut postgres::Connection<'_>, changes: &mut Vec<NewRoomChange>) -> Result<usize> {
+        let mut total_affected = 0;
+
+        // The foreign key needs the room: one that was dead-lettered, or created before
+        // indexing started, has no row. Its metadata is dead-lettered rather than failing the
+        // batch, and the lock keeps the rooms in place until the commit
+        let room_ids: Vec<&String> = self.metadata.keys().collect();
+        let indexed_rooms: HashSet<String> = if room_ids.is_empty() {
+            HashSet::new()
+        } else {
+            meeting_rooms::table
+                .filter(meeting_rooms::room_id.eq_any(room_ids))
+                .select(meeting_rooms::room_id)
+                .for_key_share()
+                .load::<String>(conn)
+                .await?
+                .into_iter()
+                .collect()
+        };
+
+        let mut dead_letters = Vec::new();
+        for (room_id, (metadata, object)) in &self.metadata {
+            if indexed_rooms.contains(room_id) {
+                continue;
+            }
+
+            tracing::warn!(room_id = %room_id, "Dead-lettering metadata of a room that is not indexed");
+            let provenance = Provenance {
+                checkpoint_sequence_number: metadata.checkpoint_sequence_number,
+                checkpoint_timestamp_ms: metadata.checkpoint_timestamp_ms,
+                transaction_digest: metadata.transaction_digest.clone(),
+                object_version: metadata.object_version,
+                package_version: metadata.package_version,
+            };
+            dead_letters.push(NewDeadLetter::object(
+                RoomProcessor::NAME,
+                object,
+                ObjectState::Live,
+                &provenance,
+                ROOM_NOT_INDEXED.to_string(),
+            )?);
+        }
+        total_affected += insert_dead_letters(&dead_letters, conn).await?;
+
+        // Delete metadata first, matching on the removed dynamic field so a newer
+        // field attached to the same room is left alone
+        if !self.deleted_fields.is_empty() {
+            let deleted = diesel::delete(room_metadata::table)
+                .filter(room_metadata::dynamic_field_id.eq_any(self.deleted_fields.keys().collect::<Vec<_>>()))
+                .execute(conn)
+                .await?;
+            total_affected += deleted;
+        }
+
+        // Upsert metadata
+        let metadata_to_upsert: Vec<NewRoomMetadata> = self.upserts(&indexed_rooms).cloned().collect();
+        for chunk in metadata_to_upsert.chunks(max_insert_rows::<NewRoomMetadata>()) {
+            total_affected += diesel::insert_into(room_metadata::table)
+                .values(chunk)
+                .on_conflict(room_metadata::room_id)
+                .do_update()
+                .set((
+                    room_metadata::dynamic_field_id.eq(excluded(room_metadata::dynamic_field_id)),
+                    room_metadata::df_version.eq(excluded(room_metadata::df_version)),
+                    room_metadata::language.eq(excluded(room_metadata::language)),
+                    room_metadata::timezone.eq(excluded(room_metadata::timezone)),
+                    room_metadata::recording_blob_id.eq(excluded(room_metadata::recording_blob_id)),
+                    room_metadata::checkpoint_sequence_number.eq(excluded(room_metadata::checkpoint_sequence_number)),
+                    room_metadata::checkpoint_timestamp_ms.eq(excluded(room_metadata::checkpoint_timestamp_ms)),
+                    room_metadata::transaction_digest.eq(excluded(room_metadata::transaction_digest)),
+                    room_metadata::object_version.eq(excluded(room_metadata::object_version)),
+                    room_metadata::package_version.eq(excluded(room_metadata::package_version)),
+                    room_metadata::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .await?;
+        }
+
+        // Keep every version, the current-state row above is overwritten
+        for chunk in self.versions.chunks(max_insert_rows::<NewRoomMetadataVersion>()) {
+            total_affected += diesel::insert_into(room_metadata_history::table)
+                .values(chunk)
+                .on_conflict((room_metadata_history::dynamic_field_id, room_metadata_history::df_version))
+                .do_nothing()
+                .execute(conn)
+                .await?;
+        }
+
+        changes.extend(self.changes(&indexed_rooms));
+
+        Ok(total_affected)
+    }
 }
 
 pub struct MetadataProcessor {
-    package_id: String,
-    dynamic_field_type: StructTag,
+    decode_error_policy: DecodeErrorPolicy,
+    metadata_type: StructTag,
 }
 
 impl MetadataProcessor {
-    pub fn new(package_id: String) -> Result<Self> {
+    pub fn new(packages: &SuimeetPackages, decode_error_policy: DecodeErrorPolicy) -> Result<Self> {
         // DynamicField type: 0x2::dynamic_field::Field<K, V>
         // K = vector<u8> (for b"metadata" key)
-        // V = MeetingMetadata from our package
-        let dynamic_field_type = parse_sui_struct_tag(&format!(
-            "0x0000000000000000000000000000000000000000000000000000000000000002::dynamic_field::Field<vector<u8>, {}::sealmeet::MeetingMetadata>",
-            package_id
-        ))?;
-
+        // V = MeetingMetadata, which keeps the original package's address across upgrades
         Ok(Self {
-            package_id,
-            dynamic_field_type,
+            decode_error_policy,
+            metadata_type: packages.struct_tag("sealmeet", "MeetingMetadata")?,
         })
     }
-}
-
-impl Processor for MetadataProcessor {
-    const NAME: &'static str = "metadata_processor";
-    type Value = ProcessedValue;
 
-    fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
-        let checkpoint_input_objs = checkpoint_input_objects(checkpoint)?;
-        let latest_live_output_objs = checkpoint_output_objects(checkpoint)?;
-
-        let mut values = Vec::new();
+    /// Upsert value for a decoded metadata dynamic field
+    fn metadata_upsert(metadata: ParsedMeetingMetadata, object: &Object, provenance: Provenance) -> Result<MetadataValue> {
+        // Convert BlobId (u256) to BigDecimal for NUMERIC storage
+        let recording_blob_id = match metadata.recording_blob_id {
+            Some(blob_id) => Some(blob_id.to_bigdecimal()?),
+            None => None,
+        };
+
+        Ok(MetadataValue::Upsert {
+            room_id: metadata.room_id.to_string(),
+            dynamic_field_id: metadata.dynamic_field_id.to_string(),
+            df_version: metadata.df_version as i64,
+            language: metadata.language,
+            timezone: metadata.timezone,
+            recording_blob_id,
+            provenance,
+            object: object.clone(),
+        })
+    }
 
+    /// Metadata values for one checkpoint, dead letters recorded under `pipeline`
+    pub fn process(
+        &self,
+        pipeline: &str,
+        checkpoint_input_objs: &BTreeMap<ObjectID, &Object>,
+        latest_live_output_objs: &BTreeMap<ObjectID, &Object>,
+        provenance: &CheckpointProvenance,
+        values: &mut Vec<ProcessedValue>,
+    ) -> Result<()> {
         // Track which room IDs have metadata in this checkpoint
         let mut processed_rooms = std::collections::HashSet::new();
 
-        // Process deletions: dynamic fields that existed in input but not in output
-        for (object_id, _object) in &checkpoint_input_objs {
-            if latest_live_output_objs.contains_key(object_id) {
-                continue; // Still exists, not deleted
-            }
-
-            // Try to extract metadata from the deleted object
-            // We need to find which room this dynamic field belonged to
-            // This is tricky because we need to track the parent object
-            // For now, we'll rely on the MetadataUpdated event to know which room was affected
-        }
-
         // Process metadata dynamic fields in output
-        for (_object_id, object) in &latest_live_output_objs {
-            // Dynamic fields have a parent field that references the MeetingRoom
-            // We need to find the room_id from the parent
-            // For this, we'll scan the parent field in the object's owner
-
-            // Try to extract the parent ObjectID from the object owner
+        for (object_id, object) in latest_live_output_objs {
+            // Dynamic fields are owned by the MeetingRoom they are attached to
             let parent_id = match &object.owner {
                 Owner::ObjectOwner(addr) => {
                     ObjectID::from(*addr)
@@ -100,150 +315,130 @@ impl Processor for MetadataProcessor {
                 _ => continue, // Not a dynamic field (not owned by object)
             };
 
-            if let Some(metadata) = extract_meeting_metadata(&self.dynamic_field_type, object, parent_id)? {
-                let room_id_str = metadata.room_id.to_string();
-
-                // Convert BlobId (u256) to BigDecimal for NUMERIC storage
-                let recording_blob_id = match metadata.recording_blob_id {
-                    Some(blob_id) => Some(blob_id.to_bigdecimal()?),
-                    None => None,
-                };
-
-                values.push(ProcessedValue::MetadataUpsert {
-                    room_id: room_id_str.clone(),
-                    dynamic_field_id: metadata.dynamic_field_id.to_string(),
-                    df_version: metadata.df_version as i64,
-                    language: metadata.language,
-                    timezone: metadata.timezone,
-                    recording_blob_id,
-                });
+            let metadata = match extract_meeting_metadata(&self.metadata_type, object, parent_id) {
+                Ok(Some(metadata)) => metadata,
+                Ok(None) => continue,
+                Err(e) => {
+                    values.push(ProcessedValue::DeadLetter(self.decode_error_policy.object_letter(
+                        pipeline,
+                        object,
+                        ObjectState::Live,
+                        &provenance.for_object(object_id),
+                        e,
+                    )?));
+                    // Don't let the input version of this field be mistaken for a removal
+                    processed_rooms.insert(parent_id.to_string());
+                    continue;
+                }
+            };
 
-                processed_rooms.insert(room_id_str);
-            }
+            processed_rooms.insert(metadata.room_id.to_string());
+            let metadata_provenance = provenance.for_object(&metadata.dynamic_field_id);
+            values.push(ProcessedValue::Metadata(Self::metadata_upsert(metadata, object, metadata_provenance)?));
         }
 
-        // Process MetadataUpdated events to detect deletions
-        // If we get a MetadataUpdated event but no corresponding dynamic field in output,
-        // it means the metadata was removed
-        for tx in &checkpoint.transactions {
-            let Some(tx_events) = &tx.events else {
-                continue;
+        // Process deletions: dynamic fields that existed in input but not in output
+        for (object_id, object) in checkpoint_input_objs {
+            if latest_live_output_objs.contains_key(object_id) {
+                continue; // Still exists, not deleted
+            }
+
+            // The input version is still owned by the room it was attached to
+            let parent_id = match &object.owner {
+                Owner::ObjectOwner(addr) => ObjectID::from(*addr),
+                _ => continue,
             };
 
-            for event in &tx_events.data {
-                if !event.package_id.to_string().starts_with(&self.package_id) {
+            let metadata = match extract_meeting_metadata(&self.metadata_type, object, parent_id) {
+                Ok(Some(metadata)) => metadata,
+                Ok(None) => continue,
+                Err(e) => {
+                    values.push(ProcessedValue::DeadLetter(self.decode_error_policy.object_letter(
+                        pipeline,
+                        object,
+                        ObjectState::removed(provenance.was_deleted(object_id)),
+                        &provenance.for_object(object_id),
+                        e,
+                    )?));
                     continue;
                 }
+            };
 
-                // Check for MetadataUpdated events
-                if event.type_.name.as_str() == "MetadataUpdated" {
-                    // Parse the event to get room_id
-                    #[derive(serde::Deserialize)]
-                    struct MetadataUpdatedEvent {
-                        room_id: move_types::ObjectId,
-                        #[allow(dead_code)]
-                        updated_by: move_types::Address,
-                    }
-
-                    if let Ok(parsed) = bcs::from_bytes::<MetadataUpdatedEvent>(&event.contents) {
-                        // Convert ObjectId to ObjectID via Address
-                        let addr = move_types::Address::from(parsed.room_id.0);
-                        let room_id_str = ObjectID::from(addr).to_string();
-
-                        // If we didn't find metadata for this room in the output objects,
-                        // it means it was deleted (or the event was emitted without metadata)
-                        // We'll handle this on the database side with proper queries
-                        // For now, just track that this room had a metadata update
-                        processed_rooms.insert(room_id_str);
-                    }
-                }
-            }
-        }
+            let room_id_str = metadata.room_id.to_string();
 
-        Ok(values)
-    }
-}
+            // A new metadata field for the same room means it was replaced (remove + add),
+            // and the upsert above already carries the new state.
+            if processed_rooms.contains(&room_id_str) {
+                tracing::debug!(
+                    room_id = %room_id_str,
+                    dynamic_field_id = %object_id,
+                    "Metadata dynamic field replaced"
+                );
+                continue;
+            }
 
-#[async_trait::async_trait]
-impl Handler for MetadataProcessor {
-    type Store = postgres::Db;
-    type Batch = Vec<ProcessedValue>;
+            values.push(ProcessedValue::Metadata(MetadataValue::Delete {
+                room_id: room_id_str,
+                dynamic_field_id: object_id.to_string(),
+                provenance: provenance.for_object(object_id),
+            }));
+        }
 
-    fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>) {
-        batch.extend(values);
+        Ok(())
     }
 
-    async fn commit<'a>(batch: &Self::Batch, conn: &mut postgres::Connection<'a>) -> Result<usize> {
-        let mut total_affected = 0;
+    /// Re-decode a dead-lettered metadata dynamic field, `None` if `object` is not one. A
+    /// field that was removed in its checkpoint is deleted rather than restored.
+    pub fn redecode(&self, object: &Object, state: ObjectState, letter: &DeadLetter) -> Result<Option<MetadataValue>> {
+        let Owner::ObjectOwner(parent) = &object.owner else {
+            return Ok(None);
+        };
 
-        // Separate by type
-        let mut metadata_to_upsert = Vec::new();
-        let mut metadata_to_delete = Vec::new();
+        let Some(metadata) = extract_meeting_metadata(&self.metadata_type, object, ObjectID::from(*parent))? else {
+            return Ok(None);
+        };
 
-        for value in batch {
-            match value {
-                ProcessedValue::MetadataUpsert { .. } => metadata_to_upsert.push(value),
-                ProcessedValue::MetadataDelete(_) => metadata_to_delete.push(value),
-            }
+        if state == ObjectState::Live {
+            return Ok(Some(Self::metadata_upsert(metadata, object, letter.provenance())?));
         }
 
-        // Delete metadata first
-        if !metadata_to_delete.is_empty() {
-            let room_ids: Vec<String> = metadata_to_delete
-                .iter()
-                .filter_map(|v| {
-                    if let ProcessedValue::MetadataDelete(id) = v {
-                        Some(id.clone())
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            let deleted = diesel::delete(room_metadata::table)
-                .filter(room_metadata::room_id.eq_any(room_ids))
-                .execute(conn)
-                .await?;
-            total_affected += deleted;
-        }
+        // The delete matches on this field's ID, so a field that replaced it is left alone
+        Ok(Some(MetadataValue::Delete {
+            room_id: metadata.room_id.to_string(),
+            dynamic_field_id: metadata.dynamic_field_id.to_string(),
+            provenance: letter.provenance(),
+        }))
+    }
 
-        // Upsert metadata
-        if !metadata_to_upsert.is_empty() {
-            for value in metadata_to_upsert {
-                if let ProcessedValue::MetadataUpsert {
-                    room_id,
-                    dynamic_field_id,
-                    df_version,
-                    language,
-                    timezone,
-                    recording_blob_id,
-                } = value {
-                    let affected = diesel::insert_into(room_metadata::table)
-                        .values(&NewRoomMetadata {
-                            room_id: room_id.clone(),
-                            dynamic_field_id: dynamic_field_id.clone(),
-                            df_version: *df_version,
-                            language: language.clone(),
-                            timezone: timezone.clone(),
-                            recording_blob_id: recording_blob_id.clone(),
-                        })
-                        .on_conflict(room_metadata::room_id)
-                        .do_update()
-                        .set((
-                            room_metadata::dynamic_field_id.eq(dynamic_field_id),
-                            room_metadata::df_version.eq(df_version),
-                            room_metadata::language.eq(language),
-                            room_metadata::timezone.eq(timezone),
-                            room_metadata::recording_blob_id.eq(recording_blob_id),
-                            room_metadata::updated_at.eq(diesel::dsl::now),
-                        ))
-                        .execute(conn)
-                        .await?;
-                    total_affected += affected;
-                }
-            }
+    /// Whether the room of a dead-lettered metadata field has been written since
+    pub async fn superseded(&self, letter: &DeadLetter, conn: &mut postgres::Connection<'_>) -> Result<bool> {
+        let object = letter.object()?;
+        if !object.type_().is_some_and(|type_| type_.is_dynamic_field()) {
+            return Ok(false);
         }
-
-        Ok(total_affected)
+        let Owner::ObjectOwner(parent) = object.owner else {
+            return Ok(false);
+        };
+
+        let room_id = ObjectID::from(parent).to_string();
+
+        // Metadata is one row per room, so any later write for the room wins
+        let newer: i64 = room_metadata::table
+            .filter(room_metadata::room_id.eq(&room_id))
+            .filter(room_metadata::checkpoint_sequence_number.gt(letter.checkpoint_sequence_number))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        // So does a later delete of the metadata or of the room, which leaves no row behind
+        let later_delete: i64 = room_changes::table
+            .filter(room_changes::room_id.eq(&room_id))
+            .filter(room_changes::change_kind.eq_any([METADATA_DELETED, ROOM_DELETED]))
+            .filter(room_changes::checkpoint_sequence_number.gt(letter.checkpoint_sequence_number))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        Ok(newer > 0 || later_delete > 0)
     }
 }
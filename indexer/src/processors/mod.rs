@@ -2,6 +2,10 @@
 
 pub mod room_processor;
 pub mod metadata_processor;
+pub mod event_processor;
+pub mod registry_processor;
 
 pub use room_processor::RoomProcessor;
 pub use metadata_processor::MetadataProcessor;
+pub use event_processor::EventProcessor;
+pub use registry_processor::RegistryProcessor;
@@ -0,0 +1,597 @@
+// Registry Processor - Handles the shared RoomRegistry and its Table entries
+
+use std::sync::Arc;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use anyhow::{bail, Result};
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel_async::RunQueryDsl;
+use sui_indexer_alt_framework::{
+    pipeline::{Processor, sequential::Handler},
+    postgres,
+    types::full_checkpoint_content::CheckpointData,
+    types::base_types::ObjectID,
+    types::object::Object,
+    types::parse_sui_struct_tag,
+    FieldCount,
+};
+use move_core_types::language_storage::StructTag;
+use sui_types::object::Owner;
+
+use crate::dead_letters::{insert_dead_letters, DecodeErrorPolicy, ObjectState, RetryDeadLetters};
+use crate::utils::{
+    checkpoint_input_objects, checkpoint_output_objects, extract_host_rooms, extract_room_registry,
+    extract_seal_room, CheckpointProvenance, ParsedRoomRegistry, Provenance, SuimeetPackages,
+};
+use crate::db::max_insert_rows;
+use crate::db::models::{
+    DeadLetter, NewDeadLetter, NewRegistryHostRooms, NewRegistrySealRoom, NewRoomRegistry, NewRoomRegistrySnapshot,
+};
+use crate::db::schema::{registry_host_rooms, registry_seal_rooms, room_registry, room_registry_snapshots};
+
+/// Enum representing the registry data to persist
+#[derive(Debug, Clone)]
+pub enum ProcessedValue {
+    /// Upsert the registry object (and record a snapshot)
+    RegistryUpsert {
+        registry_id: String,
+        room_count: i64,
+        all_rooms: Vec<String>,
+        active_rooms: Vec<String>,
+        scheduled_rooms: Vec<String>,
+        rooms_by_host_table_id: String,
+        rooms_by_seal_id_table_id: String,
+        provenance: Provenance,
+    },
+    /// Upsert a `rooms_by_host` entry
+    HostRoomsUpsert {
+        dynamic_field_id: String,
+        table_id: String,
+        host_address: String,
+        room_ids: Vec<String>,
+        provenance: Provenance,
+        /// The dynamic field, dead-lettered if its registry is not indexed
+        object: Object,
+    },
+    /// Upsert a `rooms_by_seal_id` entry
+    SealRoomUpsert {
+        dynamic_field_id: String,
+        table_id: String,
+        seal_policy_id: String,
+        room_id: String,
+        provenance: Provenance,
+        /// The dynamic field, dead-lettered if its registry is not indexed
+        object: Object,
+    },
+    /// Table entry removed (by dynamic field ID)
+    TableEntryDelete(String),
+    /// Record an object that failed to decode
+    DeadLetter(NewDeadLetter),
+}
+
+impl FieldCount for ProcessedValue {
+    const FIELD_COUNT: usize = 13;
+}
+
+/// Error recorded for a table entry whose registry has no `room_registry` row
+const REGISTRY_NOT_INDEXED: &str = "registry not indexed";
+
+/// Registry values folded, in checkpoint order, into the final state of each key
+#[derive(Debug, Default)]
+pub struct RegistryBatch {
+    /// Last state of each registry
+    registries: BTreeMap<String, NewRoomRegistry>,
+    /// Every registry version seen, for the snapshot table
+    snapshots: Vec<NewRoomRegistrySnapshot>,
+    /// Last state of each `rooms_by_host` entry by dynamic field ID, `None` once removed
+    host_rooms: BTreeMap<String, Option<TableEntry<NewRegistryHostRooms>>>,
+    /// Last state of each `rooms_by_seal_id` entry by dynamic field ID, `None` once removed
+    seal_rooms: BTreeMap<String, Option<TableEntry<NewRegistrySealRoom>>>,
+    dead_letters: Vec<NewDeadLetter>,
+}
+
+/// A table entry row with the dynamic field it was decoded from, dead-lettered if its
+/// registry is not indexed
+#[derive(Debug)]
+struct TableEntry<T> {
+    row: T,
+    object: Object,
+    provenance: Provenance,
+}
+
+impl RegistryBatch {
+    fn push(&mut self, value: ProcessedValue) {
+        match value {
+            ProcessedValue::RegistryUpsert {
+                registry_id,
+                room_count,
+                all_rooms,
+                active_rooms,
+                scheduled_rooms,
+                rooms_by_host_table_id,
+                rooms_by_seal_id_table_id,
+                provenance,
+            } => {
+                self.snapshots.push(NewRoomRegistrySnapshot {
+                    registry_id: registry_id.clone(),
+                    room_count,
+                    all_rooms: all_rooms.clone(),
+                    active_rooms: active_rooms.clone(),
+                    scheduled_rooms: scheduled_rooms.clone(),
+                    checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                    checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+                    transaction_digest: provenance.transaction_digest.clone(),
+                    object_version: provenance.object_version,
+                });
+                self.registries.insert(registry_id.clone(), NewRoomRegistry {
+                    registry_id,
+                    room_count,
+                    all_rooms,
+                    active_rooms,
+                    scheduled_rooms,
+                    rooms_by_host_table_id,
+                    rooms_by_seal_id_table_id,
+                    checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                    checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+                    transaction_digest: provenance.transaction_digest,
+                    object_version: provenance.object_version,
+                });
+            }
+            ProcessedValue::HostRoomsUpsert {
+                dynamic_field_id,
+                table_id,
+                host_address,
+                room_ids,
+                provenance,
+                object,
+            } => {
+                let row = NewRegistryHostRooms {
+                    dynamic_field_id: dynamic_field_id.clone(),
+                    table_id,
+                    host_address,
+                    room_ids,
+                    checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                    transaction_digest: provenance.transaction_digest.clone(),
+                };
+                self.host_rooms.insert(dynamic_field_id, Some(TableEntry { row, object, provenance }));
+            }
+            ProcessedValue::SealRoomUpsert {
+                dynamic_field_id,
+                table_id,
+                seal_policy_id,
+                room_id,
+                provenance,
+                object,
+            } => {
+                let row = NewRegistrySealRoom {
+                    dynamic_field_id: dynamic_field_id.clone(),
+                    table_id,
+                    seal_policy_id,
+                    room_id,
+                    checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                    transaction_digest: provenance.transaction_digest.clone(),
+                };
+                self.seal_rooms.insert(dynamic_field_id, Some(TableEntry { row, object, provenance }));
+            }
+            ProcessedValue::TableEntryDelete(dynamic_field_id) => {
+                // Deleting by ID from the table that never held it is a no-op
+                self.host_rooms.insert(dynamic_field_id.clone(), None);
+                self.seal_rooms.insert(dynamic_field_id, None);
+            }
+            ProcessedValue::DeadLetter(letter) => self.dead_letters.push(letter),
+        }
+    }
+}
+
+/// Rows of the live `entries` whose table is in `indexed_tables`, dead-lettering the others
+/// into `dead_letters`
+fn indexed_entries<T: Clone>(
+    entries: &BTreeMap<String, Option<TableEntry<T>>>,
+    indexed_tables: &HashSet<String>,
+    table_id: fn(&T) -> &String,
+    dead_letters: &mut Vec<NewDeadLetter>,
+) -> Result<Vec<T>> {
+    let mut rows = Vec::new();
+
+    for entry in entries.values().flatten() {
+        if indexed_tables.contains(table_id(&entry.row)) {
+            rows.push(entry.row.clone());
+            continue;
+        }
+
+        tracing::warn!(
+            dynamic_field_id = %entry.object.id(),
+            table_id = %table_id(&entry.row),
+            "Dead-lettering table entry of a registry that is not indexed"
+        );
+        dead_letters.push(NewDeadLetter::object(
+            RegistryProcessor::NAME,
+            &entry.object,
+            ObjectState::Live,
+            &entry.provenance,
+            REGISTRY_NOT_INDEXED.to_string(),
+        )?);
+    }
+
+    Ok(rows)
+}
+
+/// Dynamic field IDs of the removed `entries`
+fn removed_entries<T>(entries: &BTreeMap<String, Option<TableEntry<T>>>) -> Vec<&String> {
+    entries
+        .iter()
+        .filter(|(_, entry)| entry.is_none())
+        .map(|(dynamic_field_id, _)| dynamic_field_id)
+        .collect()
+}
+
+pub struct RegistryProcessor {
+    packages: SuimeetPackages,
+    decode_error_policy: DecodeErrorPolicy,
+    registry_type: StructTag,
+    host_rooms_field_type: StructTag,
+    seal_room_field_type: StructTag,
+}
+
+impl RegistryProcessor {
+    pub fn new(packages: SuimeetPackages, decode_error_policy: DecodeErrorPolicy) -> Result<Self> {
+        let registry_type = packages.struct_tag("sealmeet", "RoomRegistry")?;
+
+        // Table<address, vector<ID>> entries
+        let host_rooms_field_type = parse_sui_struct_tag(
+            "0x0000000000000000000000000000000000000000000000000000000000000002::dynamic_field::Field<address, vector<0x0000000000000000000000000000000000000000000000000000000000000002::object::ID>>",
+        )?;
+
+        // Table<ID, ID> entries
+        let seal_room_field_type = parse_sui_struct_tag(
+            "0x0000000000000000000000000000000000000000000000000000000000000002::dynamic_field::Field<0x0000000000000000000000000000000000000000000000000000000000000002::object::ID, 0x0000000000000000000000000000000000000000000000000000000000000002::object::ID>",
+        )?;
+
+        Ok(Self {
+            packages,
+            decode_error_policy,
+            registry_type,
+            host_rooms_field_type,
+            seal_room_field_type,
+        })
+    }
+
+    /// The Table owning `object`, if it has the type of a registry table entry. The types are
+    /// generic framework types, so the owner decides whether the entry is the registry's
+    fn entry_table_id(&self, object: &Object) -> Option<ObjectID> {
+        let is_entry = object.type_().is_some_and(|type_| {
+            type_.is(&self.host_rooms_field_type) || type_.is(&self.seal_room_field_type)
+        });
+
+        // Table entries are dynamic fields owned by the Table's UID
+        match &object.owner {
+            Owner::ObjectOwner(addr) if is_entry => Some(ObjectID::from(*addr)),
+            _ => None,
+        }
+    }
+
+    fn registry_upsert(registry: ParsedRoomRegistry, provenance: Provenance) -> ProcessedValue {
+        let ids = |ids: Vec<ObjectID>| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        ProcessedValue::RegistryUpsert {
+            registry_id: registry.registry_id.to_string(),
+            room_count: registry.room_count as i64,
+            all_rooms: ids(registry.all_rooms),
+            active_rooms: ids(registry.active_rooms),
+            scheduled_rooms: ids(registry.scheduled_rooms),
+            rooms_by_host_table_id: registry.rooms_by_host_table_id.to_string(),
+            rooms_by_seal_id_table_id: registry.rooms_by_seal_id_table_id.to_string(),
+            provenance,
+        }
+    }
+
+    /// Value for the current state of a table entry, `None` for any other object. Callers
+    /// check the entry's Table first
+    fn entry_value(&self, object: &Object, provenance: Provenance) -> Result<Option<ProcessedValue>> {
+        let Some(table_id) = self.entry_table_id(object) else {
+            return Ok(None);
+        };
+
+        if let Some(entry) = extract_host_rooms(&self.host_rooms_field_type, object, table_id)? {
+            return Ok(Some(ProcessedValue::HostRoomsUpsert {
+                dynamic_field_id: entry.dynamic_field_id.to_string(),
+                table_id: entry.table_id.to_string(),
+                host_address: entry.host.to_string(),
+                room_ids: entry.room_ids.iter().map(|id| id.to_string()).collect(),
+                provenance,
+                object: object.clone(),
+            }));
+        }
+
+        if let Some(entry) = extract_seal_room(&self.seal_room_field_type, object, table_id)? {
+            return Ok(Some(ProcessedValue::SealRoomUpsert {
+                dynamic_field_id: entry.dynamic_field_id.to_string(),
+                table_id: entry.table_id.to_string(),
+                seal_policy_id: entry.seal_policy_id.to_string(),
+                room_id: entry.room_id.to_string(),
+                provenance,
+                object: object.clone(),
+            }));
+        }
+
+        Ok(None)
+    }
+}
+
+impl Processor for RegistryProcessor {
+    const NAME: &'static str = "registry_processor";
+    type Value = ProcessedValue;
+
+    fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
+        let checkpoint_input_objs = checkpoint_input_objects(checkpoint)?;
+        let latest_live_output_objs = checkpoint_output_objects(checkpoint)?;
+        let provenance = CheckpointProvenance::new(checkpoint, &self.packages);
+
+        let mut values = Vec::new();
+
+        // Table entries are only added, changed or removed by a transaction that takes the
+        // registry by mutable reference, so their registry is among the checkpoint's outputs
+        let mut registry_tables = BTreeSet::new();
+        let mut undecoded_registry = false;
+
+        for (object_id, object) in &latest_live_output_objs {
+            match extract_room_registry(&self.registry_type, object) {
+                Ok(Some(registry)) => {
+                    registry_tables.insert(registry.rooms_by_host_table_id);
+                    registry_tables.insert(registry.rooms_by_seal_id_table_id);
+                    values.push(Self::registry_upsert(registry, provenance.for_object(object_id)));
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    values.push(ProcessedValue::DeadLetter(self.decode_error_policy.object_letter(
+                        Self::NAME,
+                        object,
+                        ObjectState::Live,
+                        &provenance.for_object(object_id),
+                        e,
+                    )?));
+                    undecoded_registry = true;
+                }
+            }
+        }
+
+        // The table IDs of a registry that failed to decode are unknown, so entries written by
+        // SuiMeet transactions are kept, and the commit dead-letters them while their registry
+        // is not indexed
+        let is_registry_entry = |object_id: &ObjectID, object: &Object| match self.entry_table_id(object) {
+            Some(table_id) => {
+                registry_tables.contains(&table_id)
+                    || (undecoded_registry && provenance.for_object(object_id).package_version.is_some())
+            }
+            None => false,
+        };
+
+        for (object_id, object) in &latest_live_output_objs {
+            if !is_registry_entry(object_id, object) {
+                continue;
+            }
+
+            match self.entry_value(object, provenance.for_object(object_id)) {
+                Ok(Some(value)) => values.push(value),
+                Ok(None) => {}
+                Err(e) => values.push(ProcessedValue::DeadLetter(self.decode_error_policy.object_letter(
+                    Self::NAME,
+                    object,
+                    ObjectState::Live,
+                    &provenance.for_object(object_id),
+                    e,
+                )?)),
+            }
+        }
+
+        // Table entries removed in this checkpoint (e.g. rooms_by_seal_id on end_room). Only
+        // the owner matters here, so an undecodable entry can still be removed
+        for (object_id, object) in &checkpoint_input_objs {
+            if latest_live_output_objs.contains_key(object_id) {
+                continue;
+            }
+
+            if is_registry_entry(object_id, object) {
+                values.push(ProcessedValue::TableEntryDelete(object_id.to_string()));
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler for RegistryProcessor {
+    type Store = postgres::Db;
+    type Batch = RegistryBatch;
+
+    fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>) {
+        for value in values {
+            batch.push(value);
+        }
+    }
+
+    async fn commit<'a>(batch: &Self::Batch, conn: &mut postgres::Connection<'a>) -> Result<usize> {
+        let mut total_affected = 0;
+
+        // Registries first, so table entries can be matched against their table IDs
+        let registries: Vec<NewRoomRegistry> = batch.registries.values().cloned().collect();
+        for chunk in registries.chunks(max_insert_rows::<NewRoomRegistry>()) {
+            total_affected += diesel::insert_into(room_registry::table)
+                .values(chunk)
+                .on_conflict(room_registry::registry_id)
+                .do_update()
+                .set((
+                    room_registry::room_count.eq(excluded(room_registry::room_count)),
+                    room_registry::all_rooms.eq(excluded(room_registry::all_rooms)),
+                    room_registry::active_rooms.eq(excluded(room_registry::active_rooms)),
+                    room_registry::scheduled_rooms.eq(excluded(room_registry::scheduled_rooms)),
+                    room_registry::checkpoint_sequence_number.eq(excluded(room_registry::checkpoint_sequence_number)),
+                    room_registry::checkpoint_timestamp_ms.eq(excluded(room_registry::checkpoint_timestamp_ms)),
+                    room_registry::transaction_digest.eq(excluded(room_registry::transaction_digest)),
+                    room_registry::object_version.eq(excluded(room_registry::object_version)),
+                    room_registry::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .await?;
+        }
+
+        for chunk in batch.snapshots.chunks(max_insert_rows::<NewRoomRegistrySnapshot>()) {
+            total_affected += diesel::insert_into(room_registry_snapshots::table)
+                .values(chunk)
+                .on_conflict((room_registry_snapshots::registry_id, room_registry_snapshots::object_version))
+                .do_nothing()
+                .execute(conn)
+                .await?;
+        }
+
+        // Ahead of the entries' own letters, so a retry applies a registry before its entries
+        total_affected += insert_dead_letters(&batch.dead_letters, conn).await?;
+
+        // Entries are written under an indexed registry only (this batch's are written above),
+        // and the lock keeps the registries in place until the commit
+        let table_ids: Vec<&String> = batch
+            .host_rooms
+            .values()
+            .flatten()
+            .map(|entry| &entry.row.table_id)
+            .chain(batch.seal_rooms.values().flatten().map(|entry| &entry.row.table_id))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let (host_tables, seal_tables): (HashSet<String>, HashSet<String>) = if table_ids.is_empty() {
+            Default::default()
+        } else {
+            room_registry::table
+                .filter(
+                    room_registry::rooms_by_host_table_id
+                        .eq_any(table_ids.clone())
+                        .or(room_registry::rooms_by_seal_id_table_id.eq_any(table_ids)),
+                )
+                .select((room_registry::rooms_by_host_table_id, room_registry::rooms_by_seal_id_table_id))
+                .for_key_share()
+                .load::<(String, String)>(conn)
+                .await?
+                .into_iter()
+                .unzip()
+        };
+
+        let mut dead_letters = Vec::new();
+        let host_rooms = indexed_entries(&batch.host_rooms, &host_tables, |row| &row.table_id, &mut dead_letters)?;
+        let seal_rooms = indexed_entries(&batch.seal_rooms, &seal_tables, |row| &row.table_id, &mut dead_letters)?;
+
+        // Remove entries first; each key holds its last state, so nothing removed is re-added
+        let removed_host_rooms = removed_entries(&batch.host_rooms);
+        if !removed_host_rooms.is_empty() {
+            total_affected += diesel::delete(registry_host_rooms::table)
+                .filter(registry_host_rooms::dynamic_field_id.eq_any(removed_host_rooms))
+                .execute(conn)
+                .await?;
+        }
+        let removed_seal_rooms = removed_entries(&batch.seal_rooms);
+        if !removed_seal_rooms.is_empty() {
+            total_affected += diesel::delete(registry_seal_rooms::table)
+                .filter(registry_seal_rooms::dynamic_field_id.eq_any(removed_seal_rooms))
+                .execute(conn)
+                .await?;
+        }
+
+        for chunk in host_rooms.chunks(max_insert_rows::<NewRegistryHostRooms>()) {
+            total_affected += diesel::insert_into(registry_host_rooms::table)
+                .values(chunk)
+                .on_conflict(registry_host_rooms::dynamic_field_id)
+                .do_update()
+                .set((
+                    registry_host_rooms::room_ids.eq(excluded(registry_host_rooms::room_ids)),
+                    registry_host_rooms::checkpoint_sequence_number.eq(excluded(registry_host_rooms::checkpoint_sequence_number)),
+                    registry_host_rooms::transaction_digest.eq(excluded(registry_host_rooms::transaction_digest)),
+                    registry_host_rooms::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .await?;
+        }
+
+        for chunk in seal_rooms.chunks(max_insert_rows::<NewRegistrySealRoom>()) {
+            total_affected += diesel::insert_into(registry_seal_rooms::table)
+                .values(chunk)
+                .on_conflict(registry_seal_rooms::dynamic_field_id)
+                .do_update()
+                .set((
+                    registry_seal_rooms::room_id.eq(excluded(registry_seal_rooms::room_id)),
+                    registry_seal_rooms::checkpoint_sequence_number.eq(excluded(registry_seal_rooms::checkpoint_sequence_number)),
+                    registry_seal_rooms::transaction_digest.eq(excluded(registry_seal_rooms::transaction_digest)),
+                    registry_seal_rooms::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .await?;
+        }
+
+        total_affected += insert_dead_letters(&dead_letters, conn).await?;
+
+        Ok(total_affected)
+    }
+}
+
+#[async_trait::async_trait]
+impl RetryDeadLetters for RegistryProcessor {
+    fn redecode(&self, letter: &DeadLetter) -> Result<Vec<ProcessedValue>> {
+        // Removed entries are deleted by owner alone, so every letter is of a live object. An
+        // entry's Table was checked when the letter was recorded, and is checked again by the
+        // commit
+        let object = letter.object()?;
+        let value = match extract_room_registry(&self.registry_type, &object)? {
+            Some(registry) => Some(Self::registry_upsert(registry, letter.provenance())),
+            None => self.entry_value(&object, letter.provenance())?,
+        };
+
+        match value {
+            Some(value) => Ok(vec![value]),
+            None => bail!("{} is not a RoomRegistry or one of its table entries", letter.type_tag),
+        }
+    }
+
+    async fn superseded(&self, letter: &DeadLetter, conn: &mut postgres::Connection<'_>) -> Result<bool> {
+        let Some(object_id) = &letter.object_id else {
+            return Ok(false);
+        };
+
+        let newer_registry: i64 = room_registry::table
+            .filter(room_registry::registry_id.eq(object_id))
+            .filter(room_registry::object_version.gt(letter.object_version))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        let newer_host_rooms: i64 = registry_host_rooms::table
+            .filter(registry_host_rooms::dynamic_field_id.eq(object_id))
+            .filter(registry_host_rooms::checkpoint_sequence_number.gt(letter.checkpoint_sequence_number))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        let newer_seal_room: i64 = registry_seal_rooms::table
+            .filter(registry_seal_rooms::dynamic_field_id.eq(object_id))
+            .filter(registry_seal_rooms::checkpoint_sequence_number.gt(letter.checkpoint_sequence_number))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        // A `rooms_by_seal_id` entry is removed when its room ends, leaving no row behind; a
+        // later registry that no longer lists the room as scheduled or active shows it
+        let ended_since = match self.redecode(letter)?.pop() {
+            Some(ProcessedValue::SealRoomUpsert { room_id, .. }) => {
+                let ended: i64 = room_registry::table
+                    .filter(room_registry::checkpoint_sequence_number.gt(letter.checkpoint_sequence_number))
+                    .filter(diesel::dsl::not(room_registry::active_rooms.contains(vec![room_id.clone()])))
+                    .filter(diesel::dsl::not(room_registry::scheduled_rooms.contains(vec![room_id])))
+                    .count()
+                    .get_result(conn)
+                    .await?;
+                ended > 0
+            }
+            _ => false,
+        };
+
+        Ok(newer_registry > 0 || newer_host_rooms > 0 || newer_seal_room > 0 || ended_since)
+    }
+}
@@ -1,24 +1,36 @@
 // Room Processor - Handles MeetingRoom and participant tracking
 
 use std::sync::Arc;
-use std::collections::{HashMap, HashSet};
-use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use anyhow::{bail, Result};
 use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Nullable, Text};
+use diesel::upsert::excluded;
 use diesel_async::RunQueryDsl;
 use sui_indexer_alt_framework::{
     pipeline::{Processor, sequential::Handler},
     postgres,
     types::full_checkpoint_content::CheckpointData,
-    types::base_types::ObjectID,
-    types::parse_sui_struct_tag,
+    types::object::Object,
     FieldCount,
 };
 use move_core_types::language_storage::StructTag;
+use serde_json::json;
 
-use crate::utils::{checkpoint_input_objects, checkpoint_output_objects, extract_meeting_room, extract_host_cap};
-use crate::events::MeetingRoomEvent;
-use crate::db::models::{NewMeetingRoom, NewRoomParticipant};
-use crate::db::schema::{meeting_rooms, room_participants};
+use crate::utils::{
+    checkpoint_input_objects, checkpoint_output_objects, extract_meeting_room, extract_host_cap,
+    host_cap_decoder, meeting_room_decoder, owner_parts, whitelist_drift, CheckpointProvenance,
+    ParsedHostCap, ParsedMeetingRoom, Provenance, SuimeetPackages, VersionedDecoder,
+};
+use super::metadata_processor::{MetadataBatch, MetadataProcessor, MetadataValue};
+use crate::changes::{
+    append_changes, PARTICIPANT_APPROVED, PARTICIPANT_REVOKED, ROOM_DELETED, ROOM_UPSERTED,
+};
+use crate::dead_letters::{insert_dead_letters, DecodeErrorPolicy, ObjectState, RetryDeadLetters};
+use crate::events::{EventRegistry, MeetingRoomEvent};
+use crate::db::max_insert_rows;
+use crate::db::models::{DeadLetter, NewDeadLetter, NewHostCap, NewHostCapChange, NewMeetingRoom, NewRoomChange, NewRoomParticipant, NewRoomWhitelist};
+use crate::db::schema::{host_cap_history, host_caps, meeting_rooms, room_changes, room_participants, room_whitelist};
 
 /// Enum representing the data of interest transformed from processing
 #[derive(Debug, Clone)]
@@ -27,6 +39,7 @@ pub enum ProcessedValue {
     RoomUpsert {
         room_id: String,
         title: String,
+        description: Option<String>,
         hosts: Vec<String>,
         participants: Vec<String>,
         seal_policy_id: String,
@@ -36,51 +49,639 @@ pub enum ProcessedValue {
         created_at: i64,
         started_at: Option<i64>,
         ended_at: Option<i64>,
+        provenance: Provenance,
     },
     /// Delete a MeetingRoom
-    RoomDelete(String),
-    /// Add/update participant
+    RoomDelete {
+        room_id: String,
+        provenance: Provenance,
+    },
+    /// Add/update participant (admin_cap_id is derived from host_caps at commit time)
     ParticipantUpsert {
         room_id: String,
         participant_address: String,
         role: String,
-        admin_cap_id: Option<String>,
+        provenance: Provenance,
     },
-    /// Remove participant
-    ParticipantDelete {
+    /// Guest approved by a host (GuestApproved)
+    ParticipantApproved {
         room_id: String,
         participant_address: String,
+        approved_by: String,
+        provenance: Provenance,
     },
+    /// Guest revoked by a host (GuestRevoked); the row is kept with status REVOKED
+    ParticipantRevoke {
+        room_id: String,
+        participant_address: String,
+        revoked_by: String,
+        provenance: Provenance,
+    },
+    /// Add/update a HostCap and its current owner
+    HostCapUpsert {
+        cap_id: String,
+        room_id: String,
+        granted_at: i64,
+        owner_kind: String,
+        owner_address: Option<String>,
+        provenance: Provenance,
+    },
+    /// Upsert the Seal whitelist wrapped in a MeetingRoom
+    WhitelistUpsert {
+        room_id: String,
+        seal_policy_id: String,
+        whitelist: Vec<String>,
+        whitelist_updated_at: i64,
+        missing_from_whitelist: Vec<String>,
+        extra_in_whitelist: Vec<String>,
+        provenance: Provenance,
+    },
+    /// HostCap was deleted
+    HostCapDelete {
+        cap_id: String,
+        room_id: String,
+        provenance: Provenance,
+    },
+    /// HostCap ownership changed (granted, transferred, wrapped, unwrapped or deleted)
+    HostCapChange {
+        cap_id: String,
+        room_id: String,
+        change_kind: String,
+        previous_owner_kind: Option<String>,
+        previous_owner_address: Option<String>,
+        owner_kind: Option<String>,
+        owner_address: Option<String>,
+        provenance: Provenance,
+    },
+    /// MeetingMetadata dynamic field change, committed with the rooms
+    Metadata(MetadataValue),
+    /// Record an object or event that failed to decode
+    DeadLetter(NewDeadLetter),
 }
 
 impl FieldCount for ProcessedValue {
-    const FIELD_COUNT: usize = 13;
+    const FIELD_COUNT: usize = 16;
+}
+
+/// Re-derive `admin_cap_id` for every participant of the given rooms from the HostCap each
+/// address actually owns. Rooms and caps may change in different checkpoints, so this reads
+/// committed `host_caps` state rather than whatever caps happened to be in one checkpoint.
+const REFRESH_ADMIN_CAPS_SQL: &str = "
+    UPDATE room_participants rp
+    SET admin_cap_id = (
+        SELECT hc.cap_id FROM host_caps hc
+        WHERE hc.room_id = rp.room_id
+          AND hc.owner_kind = 'ADDRESS'
+          AND hc.owner_address = rp.participant_address
+        ORDER BY hc.granted_at, hc.cap_id
+        LIMIT 1
+    )
+    WHERE rp.room_id = ANY($1)";
+
+/// Revoke delegated hosts that no longer hold a cap and are not on-chain participants,
+/// leaving at the checkpoint time their last cap moved away
+const REVOKE_DELEGATED_HOSTS_SQL: &str = "
+    UPDATE room_participants rp
+    SET status = 'REVOKED',
+        left_at = (
+            SELECT to_timestamp(MAX(h.checkpoint_timestamp_ms) / 1000.0) AT TIME ZONE 'UTC'
+            FROM host_cap_history h
+            WHERE h.room_id = rp.room_id
+              AND h.previous_owner_address = rp.participant_address
+        ),
+        updated_at = NOW()
+    FROM meeting_rooms mr
+    WHERE rp.room_id = mr.room_id
+      AND rp.status = 'ACTIVE'
+      AND rp.room_id = ANY($1)
+      AND rp.role = 'DELEGATED_HOST'
+      AND NOT (rp.participant_address = ANY(mr.participants))
+      AND NOT EXISTS (
+          SELECT 1 FROM host_caps hc
+          WHERE hc.room_id = rp.room_id
+            AND hc.owner_kind = 'ADDRESS'
+            AND hc.owner_address = rp.participant_address
+      )";
+
+/// Demote delegated hosts that no longer hold a cap but are still on-chain participants
+const DEMOTE_DELEGATED_HOSTS_SQL: &str = "
+    UPDATE room_participants rp
+    SET role = 'PARTICIPANT', updated_at = NOW()
+    WHERE rp.room_id = ANY($1)
+      AND rp.role = 'DELEGATED_HOST'
+      AND rp.status = 'ACTIVE'
+      AND NOT EXISTS (
+          SELECT 1 FROM host_caps hc
+          WHERE hc.room_id = rp.room_id
+            AND hc.owner_kind = 'ADDRESS'
+            AND hc.owner_address = rp.participant_address
+      )";
+
+/// Give DELEGATED_HOST to every address that owns a cap for the room but is not in `hosts`
+const PROMOTE_DELEGATED_HOSTS_SQL: &str = "
+    INSERT INTO room_participants (
+        room_id, participant_address, role, admin_cap_id, joined_at,
+        checkpoint_sequence_number, checkpoint_timestamp_ms, transaction_digest, object_version
+    )
+    SELECT DISTINCT ON (hc.room_id, hc.owner_address)
+        hc.room_id, hc.owner_address, 'DELEGATED_HOST', hc.cap_id,
+        to_timestamp(hc.checkpoint_timestamp_ms / 1000.0) AT TIME ZONE 'UTC',
+        hc.checkpoint_sequence_number, hc.checkpoint_timestamp_ms, hc.transaction_digest, hc.object_version
+    FROM host_caps hc
+    JOIN meeting_rooms mr ON mr.room_id = hc.room_id
+    WHERE hc.room_id = ANY($1)
+      AND hc.owner_kind = 'ADDRESS'
+      AND NOT (hc.owner_address = ANY(mr.hosts))
+    ORDER BY hc.room_id, hc.owner_address, hc.granted_at, hc.cap_id
+    ON CONFLICT (room_id, participant_address) DO UPDATE
+    SET role = 'DELEGATED_HOST',
+        admin_cap_id = EXCLUDED.admin_cap_id,
+        joined_at = CASE
+            WHEN room_participants.status = 'REVOKED' THEN EXCLUDED.joined_at
+            ELSE room_participants.joined_at
+        END,
+        status = 'ACTIVE',
+        left_at = NULL,
+        revoked_by = NULL,
+        updated_at = NOW()";
+
+/// Revoke participants (soft delete, left_at is the revoking checkpoint's time), one row of
+/// the unnested arrays per participant
+const REVOKE_PARTICIPANTS_SQL: &str = "
+    UPDATE room_participants rp
+    SET status = 'REVOKED',
+        left_at = to_timestamp(r.checkpoint_timestamp_ms / 1000.0) AT TIME ZONE 'UTC',
+        revoked_by = r.revoked_by,
+        checkpoint_sequence_number = r.checkpoint_sequence_number,
+        checkpoint_timestamp_ms = r.checkpoint_timestamp_ms,
+        transaction_digest = r.transaction_digest,
+        object_version = r.object_version,
+        package_version = r.package_version,
+        updated_at = NOW()
+    FROM unnest($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::BIGINT[], $5::BIGINT[], $6::TEXT[], $7::BIGINT[], $8::BIGINT[])
+        AS r(room_id, participant_address, revoked_by, checkpoint_sequence_number,
+             checkpoint_timestamp_ms, transaction_digest, object_version, package_version)
+    WHERE rp.room_id = r.room_id
+      AND rp.participant_address = r.participant_address";
+
+/// Revoke the hosts and participants still active in the index but no longer members of
+/// their room's latest on-chain state. One row of the first arrays per room, one row of the
+/// last two per (room, member)
+const REVOKE_FORMER_MEMBERS_SQL: &str = "
+    UPDATE room_participants rp
+    SET status = 'REVOKED',
+        left_at = to_timestamp(r.checkpoint_timestamp_ms / 1000.0) AT TIME ZONE 'UTC',
+        checkpoint_sequence_number = r.checkpoint_sequence_number,
+        checkpoint_timestamp_ms = r.checkpoint_timestamp_ms,
+        transaction_digest = r.transaction_digest,
+        object_version = r.object_version,
+        package_version = r.package_version,
+        updated_at = NOW()
+    FROM unnest($1::TEXT[], $2::BIGINT[], $3::BIGINT[], $4::TEXT[], $5::BIGINT[], $6::BIGINT[])
+        AS r(room_id, checkpoint_sequence_number, checkpoint_timestamp_ms, transaction_digest,
+             object_version, package_version)
+    WHERE rp.room_id = r.room_id
+      AND rp.status = 'ACTIVE'
+      AND rp.role IN ('HOST', 'PARTICIPANT')
+      AND NOT EXISTS (
+          SELECT 1 FROM unnest($7::TEXT[], $8::TEXT[]) AS m(room_id, address)
+          WHERE m.room_id = rp.room_id
+            AND m.address = rp.participant_address
+      )
+    RETURNING rp.room_id, rp.participant_address";
+
+/// Record who approved each guest, one row of the unnested arrays per guest. An approval later
+/// than the row's last write becomes its provenance, so retried events can tell they are stale
+const APPROVE_PARTICIPANTS_SQL: &str = "
+    UPDATE room_participants rp
+    SET approved_by = a.approved_by,
+        checkpoint_sequence_number = GREATEST(rp.checkpoint_sequence_number, a.checkpoint_sequence_number),
+        checkpoint_timestamp_ms = CASE WHEN a.checkpoint_sequence_number > rp.checkpoint_sequence_number
+            THEN a.checkpoint_timestamp_ms ELSE rp.checkpoint_timestamp_ms END,
+        transaction_digest = CASE WHEN a.checkpoint_sequence_number > rp.checkpoint_sequence_number
+            THEN a.transaction_digest ELSE rp.transaction_digest END,
+        object_version = CASE WHEN a.checkpoint_sequence_number > rp.checkpoint_sequence_number
+            THEN a.object_version ELSE rp.object_version END,
+        package_version = CASE WHEN a.checkpoint_sequence_number > rp.checkpoint_sequence_number
+            THEN a.package_version ELSE rp.package_version END,
+        updated_at = NOW()
+    FROM unnest($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::BIGINT[], $5::BIGINT[], $6::TEXT[], $7::BIGINT[], $8::BIGINT[])
+        AS a(room_id, participant_address, approved_by, checkpoint_sequence_number,
+             checkpoint_timestamp_ms, transaction_digest, object_version, package_version)
+    WHERE rp.room_id = a.room_id
+      AND rp.participant_address = a.participant_address";
+
+/// A participant revoked by `REVOKE_FORMER_MEMBERS_SQL`
+#[derive(QueryableByName, Debug)]
+struct FormerMember {
+    #[diesel(sql_type = Text)]
+    room_id: String,
+    #[diesel(sql_type = Text)]
+    participant_address: String,
+}
+
+/// Room pipeline values folded, in checkpoint order, into the final state of each key
+///
+/// A sequential batch spans many checkpoints and the framework decides how they are
+/// grouped, so `commit` must not depend on the order it writes kinds in. Folding here
+/// keeps a revoke followed by a rejoin (or the reverse) in the same batch correct.
+#[derive(Debug, Default)]
+pub struct RoomBatch {
+    /// Rooms deleted at some point in the batch; deleted before anything else is written
+    deleted_rooms: BTreeMap<String, Provenance>,
+    /// Last state of each room that is live at the end of the batch
+    rooms: BTreeMap<String, (NewMeetingRoom, Provenance)>,
+    participants: BTreeMap<(String, String), ParticipantState>,
+    /// Last state of each HostCap, `None` once deleted
+    host_caps: BTreeMap<String, Option<NewHostCap>>,
+    host_cap_changes: Vec<NewHostCapChange>,
+    whitelists: BTreeMap<String, NewRoomWhitelist>,
+    metadata: MetadataBatch,
+    dead_letters: Vec<NewDeadLetter>,
+    /// Rooms whose participants need admin_cap_id re-derived after the commit
+    rooms_to_refresh: BTreeSet<String>,
+}
+
+#[derive(Debug, Default)]
+struct ParticipantState {
+    /// Last revocation in the batch, written before `active` so a rejoin starts a new stay
+    revocation: Option<(String, Provenance)>,
+    /// Membership after the last revocation, if the participant is (back) in the room
+    active: Option<NewRoomParticipant>,
+    approved_by: Option<(String, Provenance)>,
+}
+
+impl RoomBatch {
+    fn participant(&mut self, room_id: &str, participant_address: &str) -> &mut ParticipantState {
+        self.participants
+            .entry((room_id.to_string(), participant_address.to_string()))
+            .or_default()
+    }
+
+    fn push(&mut self, value: ProcessedValue) {
+        match value {
+            ProcessedValue::RoomUpsert {
+                room_id,
+                title,
+                description,
+                hosts,
+                participants,
+                seal_policy_id,
+                status,
+                max_participants,
+                require_approval,
+                created_at,
+                started_at,
+                ended_at,
+                provenance,
+            } => {
+                let room = NewMeetingRoom {
+                    room_id: room_id.clone(),
+                    title,
+                    hosts,
+                    seal_policy_id,
+                    status,
+                    max_participants,
+                    require_approval,
+                    participant_count: participants.len() as i32,
+                    created_at,
+                    started_at,
+                    ended_at,
+                    checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                    checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+                    transaction_digest: provenance.transaction_digest.clone(),
+                    object_version: provenance.object_version,
+                    participants,
+                    description,
+                    package_version: provenance.package_version,
+                };
+                self.rooms.insert(room_id, (room, provenance));
+            }
+            ProcessedValue::RoomDelete { room_id, provenance } => {
+                // The cascade removes everything recorded for the room so far
+                self.rooms.remove(&room_id);
+                self.participants.retain(|(participant_room, _), _| *participant_room != room_id);
+                self.whitelists.remove(&room_id);
+                self.metadata.drop_room(&room_id);
+                self.deleted_rooms.insert(room_id, provenance);
+            }
+            ProcessedValue::ParticipantUpsert { room_id, participant_address, role, provenance } => {
+                self.participant(&room_id, &participant_address).active = Some(NewRoomParticipant {
+                    room_id: room_id.clone(),
+                    participant_address: participant_address.clone(),
+                    role,
+                    admin_cap_id: None,
+                    joined_at: provenance.checkpoint_time(),
+                    checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                    checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+                    transaction_digest: provenance.transaction_digest,
+                    object_version: provenance.object_version,
+                    package_version: provenance.package_version,
+                });
+                self.rooms_to_refresh.insert(room_id);
+            }
+            ProcessedValue::ParticipantRevoke { room_id, participant_address, revoked_by, provenance } => {
+                let participant = self.participant(&room_id, &participant_address);
+                participant.revocation = Some((revoked_by, provenance));
+                participant.active = None;
+            }
+            ProcessedValue::ParticipantApproved { room_id, participant_address, approved_by, provenance } => {
+                self.participant(&room_id, &participant_address).approved_by = Some((approved_by, provenance));
+            }
+            ProcessedValue::HostCapUpsert {
+                cap_id,
+                room_id,
+                granted_at,
+                owner_kind,
+                owner_address,
+                provenance,
+            } => {
+                self.rooms_to_refresh.insert(room_id.clone());
+                self.host_caps.insert(cap_id.clone(), Some(NewHostCap {
+                    cap_id,
+                    room_id,
+                    granted_at,
+                    owner_kind,
+                    owner_address,
+                    checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                    checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+                    transaction_digest: provenance.transaction_digest,
+                    object_version: provenance.object_version,
+                    package_version: provenance.package_version,
+                }));
+            }
+            ProcessedValue::HostCapDelete { cap_id, room_id, .. } => {
+                self.host_caps.insert(cap_id, None);
+                self.rooms_to_refresh.insert(room_id);
+            }
+            ProcessedValue::HostCapChange {
+                cap_id,
+                room_id,
+                change_kind,
+                previous_owner_kind,
+                previous_owner_address,
+                owner_kind,
+                owner_address,
+                provenance,
+            } => self.host_cap_changes.push(NewHostCapChange {
+                cap_id,
+                room_id,
+                change_kind,
+                previous_owner_kind,
+                previous_owner_address,
+                owner_kind,
+                owner_address,
+                checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+                transaction_digest: provenance.transaction_digest,
+                object_version: provenance.object_version,
+            }),
+            ProcessedValue::WhitelistUpsert {
+                room_id,
+                seal_policy_id,
+                whitelist,
+                whitelist_updated_at,
+                missing_from_whitelist,
+                extra_in_whitelist,
+                provenance,
+            } => {
+                let has_drift = !missing_from_whitelist.is_empty() || !extra_in_whitelist.is_empty();
+                self.whitelists.insert(room_id.clone(), NewRoomWhitelist {
+                    room_id,
+                    seal_policy_id,
+                    whitelist,
+                    whitelist_updated_at,
+                    missing_from_whitelist,
+                    extra_in_whitelist,
+                    has_drift,
+                    checkpoint_sequence_number: provenance.checkpoint_sequence_number,
+                    checkpoint_timestamp_ms: provenance.checkpoint_timestamp_ms,
+                    transaction_digest: provenance.transaction_digest,
+                    object_version: provenance.object_version,
+                });
+            }
+            ProcessedValue::Metadata(value) => self.metadata.push(value),
+            ProcessedValue::DeadLetter(letter) => self.dead_letters.push(letter),
+        }
+    }
 }
 
 pub struct RoomProcessor {
-    package_id: String,
-    meeting_room_type: StructTag,
-    host_cap_type: StructTag,
+    packages: SuimeetPackages,
+    decode_error_policy: DecodeErrorPolicy,
+    events: EventRegistry,
+    meeting_room_decoder: VersionedDecoder<ParsedMeetingRoom>,
+    host_cap_decoder: VersionedDecoder<ParsedHostCap>,
+    metadata: MetadataProcessor,
 }
 
 impl RoomProcessor {
-    pub fn new(package_id: String) -> Result<Self> {
-        let meeting_room_type = parse_sui_struct_tag(&format!(
-            "{}::sealmeet::MeetingRoom",
-            package_id
-        ))?;
-        
-        let host_cap_type = parse_sui_struct_tag(&format!(
-            "{}::sealmeet::HostCap",
-            package_id
-        ))?;
+    pub fn new(packages: SuimeetPackages, decode_error_policy: DecodeErrorPolicy) -> Result<Self> {
+        let meeting_room_decoder = meeting_room_decoder(&packages);
+        let host_cap_decoder = host_cap_decoder(&packages);
+        let events = EventRegistry::new(&packages)?;
+        let metadata = MetadataProcessor::new(&packages, decode_error_policy)?;
 
         Ok(Self {
-            package_id,
-            meeting_room_type,
-            host_cap_type,
+            packages,
+            decode_error_policy,
+            events,
+            meeting_room_decoder,
+            host_cap_decoder,
+            metadata,
         })
     }
+
+    fn is_meeting_room(&self, object: &Object) -> bool {
+        object
+            .type_()
+            .is_some_and(|type_| self.meeting_room_decoder.decodes(&StructTag::from(type_.clone())))
+    }
+
+    /// Room, whitelist and participant values for the current state of a MeetingRoom
+    fn push_room_values(
+        &self,
+        room: ParsedMeetingRoom,
+        room_provenance: Provenance,
+        values: &mut Vec<ProcessedValue>,
+    ) {
+        let room_id_str = room.object_id.to_string();
+        
+        let hosts_strings: Vec<String> = room.hosts
+            .iter()
+            .map(|addr| addr.to_string())
+            .collect();
+        
+        let participants_strings: Vec<String> = room.participants
+            .iter()
+            .map(|addr| addr.to_string())
+            .collect();
+
+        let whitelist_strings: Vec<String> = room.whitelist
+            .iter()
+            .map(|addr| addr.to_string())
+            .collect();
+
+        let started_at = if room.started_at > 0 { Some(room.started_at as i64) } else { None };
+        let ended_at = if room.ended_at > 0 { Some(room.ended_at as i64) } else { None };
+
+        // Upsert the room
+        values.push(ProcessedValue::RoomUpsert {
+            room_id: room_id_str.clone(),
+            title: room.title,
+            description: room.description,
+            hosts: hosts_strings.clone(),
+            participants: participants_strings.clone(),
+            seal_policy_id: room.seal_policy_id.to_string(),
+            status: room.status as i16,
+            max_participants: room.max_participants as i64,
+            require_approval: room.require_approval,
+            created_at: room.created_at as i64,
+            started_at,
+            ended_at,
+            provenance: room_provenance.clone(),
+        });
+
+        // Seal decryption trusts the whitelist, so flag rooms where it disagrees with participants
+        let (missing_from_whitelist, extra_in_whitelist) =
+            whitelist_drift(&participants_strings, &whitelist_strings);
+
+        if !missing_from_whitelist.is_empty() || !extra_in_whitelist.is_empty() {
+            tracing::warn!(
+                room_id = %room_id_str,
+                ?missing_from_whitelist,
+                ?extra_in_whitelist,
+                "Room participants and Seal whitelist disagree"
+            );
+        }
+
+        values.push(ProcessedValue::WhitelistUpsert {
+            room_id: room_id_str.clone(),
+            seal_policy_id: room.seal_policy_id.to_string(),
+            whitelist: whitelist_strings,
+            whitelist_updated_at: room.whitelist_updated_at as i64,
+            missing_from_whitelist,
+            extra_in_whitelist,
+            provenance: room_provenance.clone(),
+        });
+
+        // Sync participants from object state
+        // HOSTs
+        for host_addr in &hosts_strings {
+            values.push(ProcessedValue::ParticipantUpsert {
+                room_id: room_id_str.clone(),
+                participant_address: host_addr.clone(),
+                role: "HOST".to_string(),
+                provenance: room_provenance.clone(),
+            });
+        }
+        
+        // PARTICIPANTs (all participants minus hosts)
+        let host_set: HashSet<_> = hosts_strings.iter().collect();
+        for participant_addr in &participants_strings {
+            if !host_set.contains(participant_addr) {
+                values.push(ProcessedValue::ParticipantUpsert {
+                    room_id: room_id_str.clone(),
+                    participant_address: participant_addr.clone(),
+                    role: "PARTICIPANT".to_string(),
+                    provenance: room_provenance.clone(),
+                });
+            }
+        }
+    }
+
+    /// Values for a HostCap that did not survive its checkpoint: a history entry from its
+    /// last owner, then the row removed if the cap was deleted, or kept as WRAPPED so an
+    /// unwrap can restore it
+    fn push_removed_cap_values(
+        host_cap: ParsedHostCap,
+        state: ObjectState,
+        cap_provenance: Provenance,
+        values: &mut Vec<ProcessedValue>,
+    ) {
+        let cap_id = host_cap.cap_id.to_string();
+        let room_id = host_cap.room_id.to_string();
+
+        values.push(ProcessedValue::HostCapChange {
+            cap_id: cap_id.clone(),
+            room_id: room_id.clone(),
+            change_kind: state.as_str().to_string(),
+            previous_owner_kind: Some(host_cap.owner_kind.to_string()),
+            previous_owner_address: host_cap.owner_address,
+            owner_kind: None,
+            owner_address: None,
+            provenance: cap_provenance.clone(),
+        });
+
+        if state == ObjectState::Deleted {
+            values.push(ProcessedValue::HostCapDelete {
+                cap_id,
+                room_id,
+                provenance: cap_provenance,
+            });
+        } else {
+            values.push(ProcessedValue::HostCapUpsert {
+                cap_id,
+                room_id,
+                granted_at: host_cap.granted_at as i64,
+                owner_kind: "WRAPPED".to_string(),
+                owner_address: None,
+                provenance: cap_provenance,
+            });
+        }
+    }
+
+    /// Participant values for the events this pipeline tracks
+    fn push_event_values(
+        event: MeetingRoomEvent,
+        provenance: Provenance,
+        values: &mut Vec<ProcessedValue>,
+    ) {
+        match event {
+            // The cap itself is indexed from its object
+            MeetingRoomEvent::HostCapGranted(_) => {}
+            MeetingRoomEvent::GuestApproved(approved) => {
+                values.push(ProcessedValue::ParticipantApproved {
+                    room_id: approved.room_id.to_string(),
+                    participant_address: approved.guest.to_string(),
+                    approved_by: approved.approved_by.to_string(),
+                    provenance,
+                });
+            }
+            MeetingRoomEvent::GuestRevoked(revoked) => {
+                // Mark participant as revoked, keeping the row for attendance history
+                values.push(ProcessedValue::ParticipantRevoke {
+                    room_id: revoked.room_id.to_string(),
+                    participant_address: revoked.guest.to_string(),
+                    revoked_by: revoked.revoked_by.to_string(),
+                    provenance,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the guest of a dead-lettered GuestApproved/GuestRevoked event has been
+    /// written since. Room updates, approvals and revocations all stamp the participant row
+    /// with their checkpoint, and only this pipeline writes it, so it is never ahead of us.
+    async fn event_superseded(&self, letter: &DeadLetter, conn: &mut postgres::Connection<'_>) -> Result<bool> {
+        let (room_id, guest) = match self.events.decode(&letter.event()?)? {
+            Some(MeetingRoomEvent::GuestApproved(approved)) => (approved.room_id, approved.guest),
+            Some(MeetingRoomEvent::GuestRevoked(revoked)) => (revoked.room_id, revoked.guest),
+            _ => return Ok(false),
+        };
+        let (room_id, guest) = (room_id.to_string(), guest.to_string());
+
+        let newer_participant: i64 = room_participants::table
+            .filter(room_participants::room_id.eq(&room_id))
+            .filter(room_participants::participant_address.eq(&guest))
+            .filter(room_participants::checkpoint_sequence_number.gt(letter.checkpoint_sequence_number))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        Ok(newer_participant > 0)
+    }
 }
 
 impl Processor for RoomProcessor {
@@ -90,125 +691,172 @@ impl Processor for RoomProcessor {
     fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
         let checkpoint_input_objs = checkpoint_input_objects(checkpoint)?;
         let latest_live_output_objs = checkpoint_output_objects(checkpoint)?;
+        let provenance = CheckpointProvenance::new(checkpoint, &self.packages);
         
         let mut values = Vec::new();
-        
-        // Track HostCap objects to link them to host addresses
-        let mut host_cap_map: HashMap<ObjectID, String> = HashMap::new(); // room_id -> cap_id
-        
-        for (_object_id, object) in &latest_live_output_objs {
-            if let Some(host_cap) = extract_host_cap(&self.host_cap_type, object)? {
-                host_cap_map.insert(host_cap.room_id, host_cap.cap_id.to_string());
+
+        // Events first: object values are the state after the checkpoint's last
+        // transaction, so they must fold after the events that led there. A guest revoked
+        // and re-approved in one checkpoint is then back in the room, not revoked
+        for tx in &checkpoint.transactions {
+            let Some(tx_events) = &tx.events else {
+                continue;
+            };
+
+            for (event_index, event) in tx_events.data.iter().enumerate() {
+                let parsed = match self.events.decode(event) {
+                    Ok(Some(parsed)) => parsed,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        values.push(ProcessedValue::DeadLetter(self.decode_error_policy.event_letter(
+                            Self::NAME,
+                            event,
+                            event_index,
+                            &provenance.for_transaction(tx),
+                            e,
+                        )?));
+                        continue;
+                    }
+                };
+
+                Self::push_event_values(parsed, provenance.for_transaction(tx), &mut values);
             }
         }
 
-        // Process MeetingRoom deletions
+        // Process HostCaps that are no longer live (deleted or wrapped)
         for (object_id, object) in &checkpoint_input_objs {
             if latest_live_output_objs.contains_key(object_id) {
                 continue;
             }
 
-            if extract_meeting_room(&self.meeting_room_type, object)?.is_none() {
-                continue;
-            }
+            let state = ObjectState::removed(provenance.was_deleted(object_id));
+            let host_cap = match extract_host_cap(&self.host_cap_decoder, object) {
+                Ok(Some(host_cap)) => host_cap,
+                Ok(None) => continue,
+                Err(e) => {
+                    values.push(ProcessedValue::DeadLetter(self.decode_error_policy.object_letter(
+                        Self::NAME,
+                        object,
+                        state,
+                        &provenance.for_object(object_id),
+                        e,
+                    )?));
+                    continue;
+                }
+            };
 
-            values.push(ProcessedValue::RoomDelete(object_id.to_string()));
+            Self::push_removed_cap_values(host_cap, state, provenance.for_object(object_id), &mut values);
         }
 
-        // Process MeetingRoom live objects
-        for (_object_id, object) in &latest_live_output_objs {
-            let Some(room) = extract_meeting_room(&self.meeting_room_type, object)? else {
-                continue;
+        // Track live HostCap objects with their current owner
+        for (object_id, object) in &latest_live_output_objs {
+            let host_cap = match extract_host_cap(&self.host_cap_decoder, object) {
+                Ok(Some(host_cap)) => host_cap,
+                Ok(None) => continue,
+                Err(e) => {
+                    values.push(ProcessedValue::DeadLetter(self.decode_error_policy.object_letter(
+                        Self::NAME,
+                        object,
+                        ObjectState::Live,
+                        &provenance.for_object(object_id),
+                        e,
+                    )?));
+                    continue;
+                }
             };
 
-            let room_id_str = room.object_id.to_string();
-            
-            let hosts_strings: Vec<String> = room.hosts
-                .iter()
-                .map(|addr| addr.to_string())
-                .collect();
-            
-            let participants_strings: Vec<String> = room.participants
-                .iter()
-                .map(|addr| addr.to_string())
-                .collect();
+            let cap_id = host_cap.cap_id.to_string();
+            let room_id = host_cap.room_id.to_string();
+            let cap_provenance = provenance.for_object(object_id);
 
-            let started_at = if room.started_at > 0 { Some(room.started_at as i64) } else { None };
-            let ended_at = if room.ended_at > 0 { Some(room.ended_at as i64) } else { None };
+            // Only the final owner in this checkpoint is visible, so several transfers
+            // within one checkpoint collapse into a single TRANSFERRED entry
+            let previous_owner = checkpoint_input_objs
+                .get(object_id)
+                .map(|input| owner_parts(&input.owner));
 
-            // Upsert the room
-            values.push(ProcessedValue::RoomUpsert {
-                room_id: room_id_str.clone(),
-                title: room.title,
-                hosts: hosts_strings.clone(),
-                participants: participants_strings.clone(),
-                seal_policy_id: room.seal_policy_id.to_string(),
-                status: room.status as i16,
-                max_participants: room.max_participants as i64,
-                require_approval: room.require_approval,
-                created_at: room.created_at as i64,
-                started_at,
-                ended_at,
-            });
+            let change_kind = match &previous_owner {
+                Some((kind, address)) if *kind == host_cap.owner_kind && *address == host_cap.owner_address => None,
+                Some(_) => Some("TRANSFERRED"),
+                None if provenance.was_created(object_id) => Some("GRANTED"),
+                None => Some("UNWRAPPED"),
+            };
 
-            // Sync participants from object state
-            // HOSTs
-            for host_addr in &hosts_strings {
-                let admin_cap_id = host_cap_map.get(&room.object_id).cloned();
-                values.push(ProcessedValue::ParticipantUpsert {
-                    room_id: room_id_str.clone(),
-                    participant_address: host_addr.clone(),
-                    role: "HOST".to_string(),
-                    admin_cap_id,
+            if let Some(change_kind) = change_kind {
+                let (previous_owner_kind, previous_owner_address) = match previous_owner {
+                    Some((kind, address)) => (Some(kind.to_string()), address),
+                    None => (None, None),
+                };
+
+                values.push(ProcessedValue::HostCapChange {
+                    cap_id: cap_id.clone(),
+                    room_id: room_id.clone(),
+                    change_kind: change_kind.to_string(),
+                    previous_owner_kind,
+                    previous_owner_address,
+                    owner_kind: Some(host_cap.owner_kind.to_string()),
+                    owner_address: host_cap.owner_address.clone(),
+                    provenance: cap_provenance.clone(),
                 });
             }
-            
-            // PARTICIPANTs (all participants minus hosts)
-            let host_set: HashSet<_> = hosts_strings.iter().collect();
-            for participant_addr in &participants_strings {
-                if !host_set.contains(participant_addr) {
-                    values.push(ProcessedValue::ParticipantUpsert {
-                        room_id: room_id_str.clone(),
-                        participant_address: participant_addr.clone(),
-                        role: "PARTICIPANT".to_string(),
-                        admin_cap_id: None,
-                    });
-                }
-            }
+
+            values.push(ProcessedValue::HostCapUpsert {
+                cap_id,
+                room_id,
+                granted_at: host_cap.granted_at as i64,
+                owner_kind: host_cap.owner_kind.to_string(),
+                owner_address: host_cap.owner_address,
+                provenance: cap_provenance,
+            });
         }
 
-        // Process events for additional tracking
-        for tx in &checkpoint.transactions {
-            let Some(tx_events) = &tx.events else {
+        // Process MeetingRoom deletions
+        for (object_id, object) in &checkpoint_input_objs {
+            if latest_live_output_objs.contains_key(object_id) {
                 continue;
-            };
+            }
 
-            for event in &tx_events.data {
-                if !event.package_id.to_string().starts_with(&self.package_id) {
-                    continue;
-                }
+            // Only the type matters here, so an undecodable room can still be deleted
+            if !self.is_meeting_room(object) {
+                continue;
+            }
 
-                let Some(parsed) = MeetingRoomEvent::from_sui_event(event)? else {
-                    continue;
-                };
+            values.push(ProcessedValue::RoomDelete {
+                room_id: object_id.to_string(),
+                provenance: provenance.for_object(object_id),
+            });
+        }
 
-                match parsed {
-                    MeetingRoomEvent::HostCapGranted(_granted) => {
-                        // Note: HostCap tracking is done via object scanning above
-                        // This event could be used for historical tracking if needed
-                    }
-                    MeetingRoomEvent::GuestRevoked(revoked) => {
-                        // Remove participant
-                        values.push(ProcessedValue::ParticipantDelete {
-                            room_id: revoked.room_id.to_string(),
-                            participant_address: revoked.guest.to_string(),
-                        });
-                    }
-                    _ => {}
+        // Process MeetingRoom live objects
+        for (object_id, object) in &latest_live_output_objs {
+            let room = match extract_meeting_room(&self.meeting_room_decoder, object) {
+                Ok(Some(room)) => room,
+                Ok(None) => continue,
+                Err(e) => {
+                    values.push(ProcessedValue::DeadLetter(self.decode_error_policy.object_letter(
+                        Self::NAME,
+                        object,
+                        ObjectState::Live,
+                        &provenance.for_object(object_id),
+                        e,
+                    )?));
+                    continue;
                 }
-            }
+            };
+
+            let room_provenance = provenance.for_object(&room.object_id);
+            self.push_room_values(room, room_provenance, &mut values);
         }
 
+        // Metadata dynamic fields, after the rooms they hang off
+        self.metadata.process(
+            Self::NAME,
+            &checkpoint_input_objs,
+            &latest_live_output_objs,
+            &provenance,
+            &mut values,
+        )?;
+
         Ok(values)
     }
 }
@@ -216,149 +864,707 @@ impl Processor for RoomProcessor {
 #[async_trait::async_trait]
 impl Handler for RoomProcessor {
     type Store = postgres::Db;
-    type Batch = Vec<ProcessedValue>;
+    type Batch = RoomBatch;
 
     fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>) {
-        batch.extend(values);
+        for value in values {
+            batch.push(value);
+        }
     }
 
     async fn commit<'a>(batch: &Self::Batch, conn: &mut postgres::Connection<'a>) -> Result<usize> {
         let mut total_affected = 0;
+        let mut changes = Vec::new();
 
-        // Separate by type
-        let mut rooms_to_upsert = Vec::new();
-        let mut rooms_to_delete = Vec::new();
-        let mut participants_to_upsert = Vec::new();
-        let mut participants_to_delete = Vec::new();
-
-        for value in batch {
-            match value {
-                ProcessedValue::RoomUpsert { .. } => rooms_to_upsert.push(value),
-                ProcessedValue::RoomDelete(_) => rooms_to_delete.push(value),
-                ProcessedValue::ParticipantUpsert { .. } => participants_to_upsert.push(value),
-                ProcessedValue::ParticipantDelete { .. } => participants_to_delete.push(value),
-            }
-        }
-
-        // Delete rooms first (CASCADE will delete participants)
-        if !rooms_to_delete.is_empty() {
-            let room_ids: Vec<String> = rooms_to_delete
-                .iter()
-                .filter_map(|v| {
-                    if let ProcessedValue::RoomDelete(id) = v {
-                        Some(id.clone())
-                    } else {
-                        None
-                    }
-                })
-                .collect();
+        // Who is active before this batch, to tell joins from members re-synced by a room update
+        let participant_rooms: Vec<&String> = batch
+            .participants
+            .keys()
+            .map(|(room_id, _)| room_id)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let active_before: HashSet<(String, String)> = if participant_rooms.is_empty() {
+            HashSet::new()
+        } else {
+            room_participants::table
+                .filter(room_participants::room_id.eq_any(participant_rooms))
+                .filter(room_participants::status.eq("ACTIVE"))
+                .select((room_participants::room_id, room_participants::participant_address))
+                .load::<(String, String)>(conn)
+                .await?
+                .into_iter()
+                .collect()
+        };
 
+        // Delete rooms first (CASCADE will delete participants); a room re-created later in
+        // the batch is written again below
+        if !batch.deleted_rooms.is_empty() {
             let deleted = diesel::delete(meeting_rooms::table)
-                .filter(meeting_rooms::room_id.eq_any(room_ids))
+                .filter(meeting_rooms::room_id.eq_any(batch.deleted_rooms.keys().collect::<Vec<_>>()))
                 .execute(conn)
                 .await?;
             total_affected += deleted;
+
+            for (room_id, provenance) in &batch.deleted_rooms {
+                changes.push(NewRoomChange::new(room_id, ROOM_DELETED, vec![], json!({}), provenance));
+            }
         }
 
+        // Versions already indexed, so a room the batch leaves as it was (a replayed
+        // checkpoint) is not announced again
+        let indexed_versions: HashMap<String, i64> = if batch.rooms.is_empty() {
+            HashMap::new()
+        } else {
+            meeting_rooms::table
+                .filter(meeting_rooms::room_id.eq_any(batch.rooms.keys().collect::<Vec<_>>()))
+                .select((meeting_rooms::room_id, meeting_rooms::object_version))
+                .load::<(String, i64)>(conn)
+                .await?
+                .into_iter()
+                .collect()
+        };
+
         // Upsert rooms
-        if !rooms_to_upsert.is_empty() {
-            for value in rooms_to_upsert {
-                if let ProcessedValue::RoomUpsert {
-                    room_id,
-                    title,
-                    hosts,
-                    participants,
-                    seal_policy_id,
-                    status,
-                    max_participants,
-                    require_approval,
-                    created_at,
-                    started_at,
-                    ended_at,
-                } = value {
-                    let participant_count = participants.len() as i32;
-                    
-                    let affected = diesel::insert_into(meeting_rooms::table)
-                        .values(&NewMeetingRoom {
-                            room_id: room_id.clone(),
-                            title: title.clone(),
-                            hosts: hosts.clone(),
-                            seal_policy_id: seal_policy_id.clone(),
-                            status: *status,
-                            max_participants: *max_participants,
-                            require_approval: *require_approval,
-                            participant_count,
-                            created_at: *created_at,
-                            started_at: *started_at,
-                            ended_at: *ended_at,
-                            checkpoint_sequence_number: 0,
-                            transaction_digest: String::new(),
-                        })
-                        .on_conflict(meeting_rooms::room_id)
-                        .do_update()
-                        .set((
-                            meeting_rooms::title.eq(title),
-                            meeting_rooms::hosts.eq(hosts),
-                            meeting_rooms::seal_policy_id.eq(seal_policy_id),
-                            meeting_rooms::status.eq(status),
-                            meeting_rooms::max_participants.eq(max_participants),
-                            meeting_rooms::require_approval.eq(require_approval),
-                            meeting_rooms::participant_count.eq(participant_count),
-                            meeting_rooms::started_at.eq(started_at),
-                            meeting_rooms::ended_at.eq(ended_at),
-                            meeting_rooms::updated_at.eq(diesel::dsl::now),
-                        ))
-                        .execute(conn)
-                        .await?;
-                    total_affected += affected;
-                }
+        let rooms_to_upsert: Vec<NewMeetingRoom> = batch.rooms.values().map(|(room, _)| room.clone()).collect();
+        for chunk in rooms_to_upsert.chunks(max_insert_rows::<NewMeetingRoom>()) {
+            total_affected += diesel::insert_into(meeting_rooms::table)
+                .values(chunk)
+                .on_conflict(meeting_rooms::room_id)
+                .do_update()
+                .set((
+                    meeting_rooms::title.eq(excluded(meeting_rooms::title)),
+                    meeting_rooms::description.eq(excluded(meeting_rooms::description)),
+                    meeting_rooms::hosts.eq(excluded(meeting_rooms::hosts)),
+                    meeting_rooms::seal_policy_id.eq(excluded(meeting_rooms::seal_policy_id)),
+                    meeting_rooms::status.eq(excluded(meeting_rooms::status)),
+                    meeting_rooms::max_participants.eq(excluded(meeting_rooms::max_participants)),
+                    meeting_rooms::require_approval.eq(excluded(meeting_rooms::require_approval)),
+                    meeting_rooms::participant_count.eq(excluded(meeting_rooms::participant_count)),
+                    meeting_rooms::participants.eq(excluded(meeting_rooms::participants)),
+                    meeting_rooms::started_at.eq(excluded(meeting_rooms::started_at)),
+                    meeting_rooms::ended_at.eq(excluded(meeting_rooms::ended_at)),
+                    meeting_rooms::checkpoint_sequence_number.eq(excluded(meeting_rooms::checkpoint_sequence_number)),
+                    meeting_rooms::checkpoint_timestamp_ms.eq(excluded(meeting_rooms::checkpoint_timestamp_ms)),
+                    meeting_rooms::transaction_digest.eq(excluded(meeting_rooms::transaction_digest)),
+                    meeting_rooms::object_version.eq(excluded(meeting_rooms::object_version)),
+                    meeting_rooms::package_version.eq(excluded(meeting_rooms::package_version)),
+                    meeting_rooms::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .await?;
+        }
+
+        for (room_id, (room, provenance)) in &batch.rooms {
+            if matches!(indexed_versions.get(room_id), Some(version) if *version >= room.object_version) {
+                continue;
             }
+
+            changes.push(NewRoomChange::new(
+                room_id,
+                ROOM_UPSERTED,
+                room.hosts.iter().chain(&room.participants).cloned().collect(),
+                json!({
+                    "title": room.title,
+                    "status": room.status,
+                    "hosts": room.hosts,
+                    "participants": room.participants,
+                    "participant_count": room.participant_count,
+                    "started_at": room.started_at,
+                    "ended_at": room.ended_at,
+                }),
+                provenance,
+            ));
         }
 
-        // Delete participants
-        if !participants_to_delete.is_empty() {
-            for value in participants_to_delete {
-                if let ProcessedValue::ParticipantDelete { room_id, participant_address } = value {
-                    let deleted = diesel::delete(room_participants::table)
-                        .filter(room_participants::room_id.eq(room_id))
-                        .filter(room_participants::participant_address.eq(participant_address))
-                        .execute(conn)
-                        .await?;
-                    total_affected += deleted;
-                }
+        // Revoke participants, before the upserts below so a rejoin starts a new stay
+        let revocations: Vec<(&String, &String, &String, &Provenance)> = batch
+            .participants
+            .iter()
+            .filter_map(|((room_id, participant_address), participant)| {
+                let (revoked_by, provenance) = participant.revocation.as_ref()?;
+                Some((room_id, participant_address, revoked_by, provenance))
+            })
+            .collect();
+        if !revocations.is_empty() {
+            total_affected += diesel::sql_query(REVOKE_PARTICIPANTS_SQL)
+                .bind::<Array<Text>, _>(revocations.iter().map(|r| r.0).collect::<Vec<_>>())
+                .bind::<Array<Text>, _>(revocations.iter().map(|r| r.1).collect::<Vec<_>>())
+                .bind::<Array<Text>, _>(revocations.iter().map(|r| r.2).collect::<Vec<_>>())
+                .bind::<Array<BigInt>, _>(revocations.iter().map(|r| r.3.checkpoint_sequence_number).collect::<Vec<_>>())
+                .bind::<Array<BigInt>, _>(revocations.iter().map(|r| r.3.checkpoint_timestamp_ms).collect::<Vec<_>>())
+                .bind::<Array<Text>, _>(revocations.iter().map(|r| &r.3.transaction_digest).collect::<Vec<_>>())
+                .bind::<Array<BigInt>, _>(revocations.iter().map(|r| r.3.object_version).collect::<Vec<_>>())
+                .bind::<Array<Nullable<BigInt>>, _>(revocations.iter().map(|r| r.3.package_version).collect::<Vec<_>>())
+                .execute(conn)
+                .await?;
+        }
+
+        for (room_id, participant_address, revoked_by, provenance) in &revocations {
+            changes.push(NewRoomChange::new(
+                room_id,
+                PARTICIPANT_REVOKED,
+                vec![(*participant_address).clone()],
+                json!({ "participant_address": participant_address, "revoked_by": revoked_by }),
+                provenance,
+            ));
+        }
+
+        // Upsert participants still in their room. Back after a revocation, in this batch
+        // or an earlier one: a new stay starts now
+        let participants_to_upsert: Vec<NewRoomParticipant> = batch
+            .participants
+            .values()
+            .filter_map(|participant| participant.active.clone())
+            .collect();
+
+        // New members, and members back after a revocation in this batch
+        for ((room_id, participant_address), participant) in &batch.participants {
+            let Some(active) = &participant.active else {
+                continue;
+            };
+
+            let key = (room_id.clone(), participant_address.clone());
+            if participant.revocation.is_some() || !active_before.contains(&key) {
+                changes.push(NewRoomChange::participant_joined(active));
             }
         }
 
-        // Upsert participants
-        if !participants_to_upsert.is_empty() {
-            for value in participants_to_upsert {
-                if let ProcessedValue::ParticipantUpsert {
+        for chunk in participants_to_upsert.chunks(max_insert_rows::<NewRoomParticipant>()) {
+            total_affected += diesel::insert_into(room_participants::table)
+                .values(chunk)
+                .on_conflict((room_participants::room_id, room_participants::participant_address))
+                .do_update()
+                .set((
+                    room_participants::role.eq(excluded(room_participants::role)),
+                    room_participants::status.eq("ACTIVE"),
+                    room_participants::joined_at.eq(diesel::dsl::sql::<diesel::sql_types::Timestamp>(
+                        "CASE WHEN room_participants.status = 'REVOKED' \
+                         THEN excluded.joined_at ELSE room_participants.joined_at END",
+                    )),
+                    room_participants::left_at.eq(None::<chrono::NaiveDateTime>),
+                    room_participants::revoked_by.eq(None::<String>),
+                    room_participants::checkpoint_sequence_number.eq(excluded(room_participants::checkpoint_sequence_number)),
+                    room_participants::checkpoint_timestamp_ms.eq(excluded(room_participants::checkpoint_timestamp_ms)),
+                    room_participants::transaction_digest.eq(excluded(room_participants::transaction_digest)),
+                    room_participants::object_version.eq(excluded(room_participants::object_version)),
+                    room_participants::package_version.eq(excluded(room_participants::package_version)),
+                    room_participants::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .await?;
+        }
+
+        let mut rooms_to_refresh = batch.rooms_to_refresh.clone();
+
+        // Reconcile membership against the latest on-chain state of each room: anyone still
+        // active in the index but no longer in hosts/participants has left, whatever the path
+        if !batch.rooms.is_empty() {
+            let rooms: Vec<(&String, &Provenance)> = batch
+                .rooms
+                .iter()
+                .map(|(room_id, (_, provenance))| (room_id, provenance))
+                .collect();
+            let (member_rooms, members): (Vec<&String>, Vec<&String>) = batch
+                .rooms
+                .iter()
+                .flat_map(|(room_id, (room, _))| {
+                    room.hosts.iter().chain(&room.participants).map(move |member| (room_id, member))
+                })
+                .unzip();
+
+            let mut removed: Vec<FormerMember> = diesel::sql_query(REVOKE_FORMER_MEMBERS_SQL)
+                .bind::<Array<Text>, _>(rooms.iter().map(|r| r.0).collect::<Vec<_>>())
+                .bind::<Array<BigInt>, _>(rooms.iter().map(|r| r.1.checkpoint_sequence_number).collect::<Vec<_>>())
+                .bind::<Array<BigInt>, _>(rooms.iter().map(|r| r.1.checkpoint_timestamp_ms).collect::<Vec<_>>())
+                .bind::<Array<Text>, _>(rooms.iter().map(|r| &r.1.transaction_digest).collect::<Vec<_>>())
+                .bind::<Array<BigInt>, _>(rooms.iter().map(|r| r.1.object_version).collect::<Vec<_>>())
+                .bind::<Array<Nullable<BigInt>>, _>(rooms.iter().map(|r| r.1.package_version).collect::<Vec<_>>())
+                .bind::<Array<Text>, _>(member_rooms)
+                .bind::<Array<Text>, _>(members)
+                .load(conn)
+                .await?;
+
+            // RETURNING order is unspecified; announce in room and address order
+            removed.sort_by(|a, b| (&a.room_id, &a.participant_address).cmp(&(&b.room_id, &b.participant_address)));
+
+            for FormerMember { room_id, participant_address } in &removed {
+                let (_, provenance) = &batch.rooms[room_id];
+                changes.push(NewRoomChange::new(
                     room_id,
-                    participant_address,
-                    role,
-                    admin_cap_id,
-                } = value {
-                    let affected = diesel::insert_into(room_participants::table)
-                        .values(&NewRoomParticipant {
-                            room_id: room_id.clone(),
-                            participant_address: participant_address.clone(),
-                            role: role.clone(),
-                            admin_cap_id: admin_cap_id.clone(),
-                        })
-                        .on_conflict((room_participants::room_id, room_participants::participant_address))
-                        .do_update()
-                        .set((
-                            room_participants::role.eq(role),
-                            room_participants::admin_cap_id.eq(admin_cap_id),
-                            room_participants::updated_at.eq(diesel::dsl::now),
-                        ))
-                        .execute(conn)
-                        .await?;
-                    total_affected += affected;
-                }
+                    PARTICIPANT_REVOKED,
+                    vec![participant_address.clone()],
+                    json!({ "participant_address": participant_address, "revoked_by": null }),
+                    provenance,
+                ));
+                rooms_to_refresh.insert(room_id.clone());
+            }
+
+            if !removed.is_empty() {
+                tracing::debug!(?removed, "Participants no longer in room object state");
+                total_affected += removed.len();
             }
         }
 
+        // Record who approved each guest
+        let approvals: Vec<(&String, &String, &String, &Provenance)> = batch
+            .participants
+            .iter()
+            .filter_map(|((room_id, participant_address), participant)| {
+                let (approved_by, provenance) = participant.approved_by.as_ref()?;
+                Some((room_id, participant_address, approved_by, provenance))
+            })
+            .collect();
+        if !approvals.is_empty() {
+            total_affected += diesel::sql_query(APPROVE_PARTICIPANTS_SQL)
+                .bind::<Array<Text>, _>(approvals.iter().map(|a| a.0).collect::<Vec<_>>())
+                .bind::<Array<Text>, _>(approvals.iter().map(|a| a.1).collect::<Vec<_>>())
+                .bind::<Array<Text>, _>(approvals.iter().map(|a| a.2).collect::<Vec<_>>())
+                .bind::<Array<BigInt>, _>(approvals.iter().map(|a| a.3.checkpoint_sequence_number).collect::<Vec<_>>())
+                .bind::<Array<BigInt>, _>(approvals.iter().map(|a| a.3.checkpoint_timestamp_ms).collect::<Vec<_>>())
+                .bind::<Array<Text>, _>(approvals.iter().map(|a| &a.3.transaction_digest).collect::<Vec<_>>())
+                .bind::<Array<BigInt>, _>(approvals.iter().map(|a| a.3.object_version).collect::<Vec<_>>())
+                .bind::<Array<Nullable<BigInt>>, _>(approvals.iter().map(|a| a.3.package_version).collect::<Vec<_>>())
+                .execute(conn)
+                .await?;
+        }
+
+        for (room_id, participant_address, approved_by, provenance) in &approvals {
+            changes.push(NewRoomChange::new(
+                room_id,
+                PARTICIPANT_APPROVED,
+                vec![(*participant_address).clone(), (*approved_by).clone()],
+                json!({ "participant_address": participant_address, "approved_by": approved_by }),
+                provenance,
+            ));
+        }
+
+        // Delete HostCaps that are no longer live
+        let caps_to_delete: Vec<&String> = batch
+            .host_caps
+            .iter()
+            .filter(|(_, cap)| cap.is_none())
+            .map(|(cap_id, _)| cap_id)
+            .collect();
+        if !caps_to_delete.is_empty() {
+            total_affected += diesel::delete(host_caps::table)
+                .filter(host_caps::cap_id.eq_any(caps_to_delete))
+                .execute(conn)
+                .await?;
+        }
+
+        // Upsert HostCaps
+        let caps_to_upsert: Vec<NewHostCap> = batch.host_caps.values().flatten().cloned().collect();
+        for chunk in caps_to_upsert.chunks(max_insert_rows::<NewHostCap>()) {
+            total_affected += diesel::insert_into(host_caps::table)
+                .values(chunk)
+                .on_conflict(host_caps::cap_id)
+                .do_update()
+                .set((
+                    host_caps::owner_kind.eq(excluded(host_caps::owner_kind)),
+                    host_caps::owner_address.eq(excluded(host_caps::owner_address)),
+                    host_caps::checkpoint_sequence_number.eq(excluded(host_caps::checkpoint_sequence_number)),
+                    host_caps::checkpoint_timestamp_ms.eq(excluded(host_caps::checkpoint_timestamp_ms)),
+                    host_caps::transaction_digest.eq(excluded(host_caps::transaction_digest)),
+                    host_caps::object_version.eq(excluded(host_caps::object_version)),
+                    host_caps::package_version.eq(excluded(host_caps::package_version)),
+                    host_caps::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .await?;
+        }
+
+        // Append HostCap ownership history (idempotent on replay)
+        for chunk in batch.host_cap_changes.chunks(max_insert_rows::<NewHostCapChange>()) {
+            total_affected += diesel::insert_into(host_cap_history::table)
+                .values(chunk)
+                .on_conflict((host_cap_history::cap_id, host_cap_history::object_version))
+                .do_nothing()
+                .execute(conn)
+                .await?;
+        }
+
+        // Upsert Seal whitelists (after rooms, for the FK)
+        let whitelists_to_upsert: Vec<NewRoomWhitelist> = batch.whitelists.values().cloned().collect();
+        for chunk in whitelists_to_upsert.chunks(max_insert_rows::<NewRoomWhitelist>()) {
+            total_affected += diesel::insert_into(room_whitelist::table)
+                .values(chunk)
+                .on_conflict(room_whitelist::room_id)
+                .do_update()
+                .set((
+                    room_whitelist::seal_policy_id.eq(excluded(room_whitelist::seal_policy_id)),
+                    room_whitelist::whitelist.eq(excluded(room_whitelist::whitelist)),
+                    room_whitelist::whitelist_updated_at.eq(excluded(room_whitelist::whitelist_updated_at)),
+                    room_whitelist::missing_from_whitelist.eq(excluded(room_whitelist::missing_from_whitelist)),
+                    room_whitelist::extra_in_whitelist.eq(excluded(room_whitelist::extra_in_whitelist)),
+                    room_whitelist::has_drift.eq(excluded(room_whitelist::has_drift)),
+                    room_whitelist::checkpoint_sequence_number.eq(excluded(room_whitelist::checkpoint_sequence_number)),
+                    room_whitelist::checkpoint_timestamp_ms.eq(excluded(room_whitelist::checkpoint_timestamp_ms)),
+                    room_whitelist::transaction_digest.eq(excluded(room_whitelist::transaction_digest)),
+                    room_whitelist::object_version.eq(excluded(room_whitelist::object_version)),
+                    room_whitelist::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .await?;
+        }
+
+        // Ahead of the metadata's own letters, so a retry applies a room before its metadata
+        total_affected += insert_dead_letters(&batch.dead_letters, conn).await?;
+
+        // Metadata last among the room tables: its foreign key needs this batch's rooms
+        total_affected += batch.metadata.commit(conn, &mut changes).await?;
+
+        // Link each participant to the HostCap it owns and sync delegated hosts
+        if !rooms_to_refresh.is_empty() {
+            let room_ids: Vec<String> = rooms_to_refresh.into_iter().collect();
+
+            for query in [
+                REFRESH_ADMIN_CAPS_SQL,
+                REVOKE_DELEGATED_HOSTS_SQL,
+                DEMOTE_DELEGATED_HOSTS_SQL,
+                PROMOTE_DELEGATED_HOSTS_SQL,
+            ] {
+                total_affected += diesel::sql_query(query)
+                    .bind::<Array<Text>, _>(room_ids.clone())
+                    .execute(conn)
+                    .await?;
+            }
+        }
+
+        // Last, holding the change log lock only until the commit
+        total_affected += append_changes(&changes, conn).await?;
+
         Ok(total_affected)
     }
 }
+
+#[async_trait::async_trait]
+impl RetryDeadLetters for RoomProcessor {
+    fn redecode(&self, letter: &DeadLetter) -> Result<Vec<ProcessedValue>> {
+        let provenance = letter.provenance();
+        let mut values = Vec::new();
+
+        if letter.kind == "EVENT" {
+            if let Some(parsed) = self.events.decode(&letter.event()?)? {
+                Self::push_event_values(parsed, provenance, &mut values);
+            }
+            return Ok(values);
+        }
+
+        let object = letter.object()?;
+        let state = letter.object_state()?;
+
+        if let Some(host_cap) = extract_host_cap(&self.host_cap_decoder, &object)? {
+            if state == ObjectState::Live {
+                // Ownership history needs the surrounding checkpoint, so a recovered live
+                // cap only restores its current state
+                values.push(ProcessedValue::HostCapUpsert {
+                    cap_id: host_cap.cap_id.to_string(),
+                    room_id: host_cap.room_id.to_string(),
+                    granted_at: host_cap.granted_at as i64,
+                    owner_kind: host_cap.owner_kind.to_string(),
+                    owner_address: host_cap.owner_address,
+                    provenance,
+                });
+            } else {
+                // The stored object is the cap before it was removed, so its owner is the
+                // previous owner
+                Self::push_removed_cap_values(host_cap, state, provenance, &mut values);
+            }
+        } else if let Some(room) = extract_meeting_room(&self.meeting_room_decoder, &object)? {
+            self.push_room_values(room, provenance, &mut values);
+        } else if let Some(metadata) = self.metadata.redecode(&object, state, letter)? {
+            values.push(ProcessedValue::Metadata(metadata));
+        } else {
+            bail!("{} is not a MeetingRoom, HostCap or MeetingMetadata field", letter.type_tag);
+        }
+
+        Ok(values)
+    }
+
+    async fn superseded(&self, letter: &DeadLetter, conn: &mut postgres::Connection<'_>) -> Result<bool> {
+        let Some(object_id) = &letter.object_id else {
+            return self.event_superseded(letter, conn).await;
+        };
+
+        let newer_room: i64 = meeting_rooms::table
+            .filter(meeting_rooms::room_id.eq(object_id))
+            .filter(meeting_rooms::object_version.gt(letter.object_version))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        let newer_cap: i64 = host_caps::table
+            .filter(host_caps::cap_id.eq(object_id))
+            .filter(host_caps::object_version.gt(letter.object_version))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        // A later delete leaves no newer row behind, only its record
+        let later_cap_delete: i64 = host_cap_history::table
+            .filter(host_cap_history::cap_id.eq(object_id))
+            .filter(host_cap_history::change_kind.eq("DELETED"))
+            .filter(host_cap_history::checkpoint_sequence_number.gt(letter.checkpoint_sequence_number))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        let later_room_delete: i64 = room_changes::table
+            .filter(room_changes::room_id.eq(object_id))
+            .filter(room_changes::change_kind.eq(ROOM_DELETED))
+            .filter(room_changes::checkpoint_sequence_number.gt(letter.checkpoint_sequence_number))
+            .count()
+            .get_result(conn)
+            .await?;
+
+        Ok(newer_room > 0
+            || newer_cap > 0
+            || later_cap_delete > 0
+            || later_room_delete > 0
+            || self.metadata.superseded(letter, conn).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+    use sui_indexer_alt_framework::types::base_types::{ObjectID, SuiAddress};
+    use sui_types::object::Owner;
+
+    const ROOM: &str = "0x1";
+    const GUEST: &str = "0xa";
+
+    fn at(checkpoint: i64) -> Provenance {
+        Provenance {
+            checkpoint_sequence_number: checkpoint,
+            checkpoint_timestamp_ms: checkpoint * 1000,
+            transaction_digest: format!("tx{checkpoint}"),
+            object_version: checkpoint,
+            package_version: Some(1),
+        }
+    }
+
+    fn join(checkpoint: i64) -> ProcessedValue {
+        ProcessedValue::ParticipantUpsert {
+            room_id: ROOM.to_string(),
+            participant_address: GUEST.to_string(),
+            role: "PARTICIPANT".to_string(),
+            provenance: at(checkpoint),
+        }
+    }
+
+    fn revoke(checkpoint: i64) -> ProcessedValue {
+        ProcessedValue::ParticipantRevoke {
+            room_id: ROOM.to_string(),
+            participant_address: GUEST.to_string(),
+            revoked_by: "0xb".to_string(),
+            provenance: at(checkpoint),
+        }
+    }
+
+    fn folded(values: Vec<ProcessedValue>) -> RoomBatch {
+        let mut batch = RoomBatch::default();
+        RoomProcessor::batch(&mut batch, values);
+        batch
+    }
+
+    #[test]
+    fn test_rejoin_after_revoke_stays_active() {
+        let batch = folded(vec![revoke(10), join(12)]);
+        let guest = &batch.participants[&(ROOM.to_string(), GUEST.to_string())];
+
+        assert_eq!(guest.revocation.as_ref().map(|(_, p)| p.checkpoint_sequence_number), Some(10));
+        assert_eq!(guest.active.as_ref().map(|p| p.checkpoint_sequence_number), Some(12));
+    }
+
+    #[test]
+    fn test_revoke_after_join_stays_revoked() {
+        let batch = folded(vec![join(10), revoke(12)]);
+        let guest = &batch.participants[&(ROOM.to_string(), GUEST.to_string())];
+
+        assert!(guest.revocation.is_some());
+        assert!(guest.active.is_none());
+    }
+
+    #[test]
+    fn test_revoke_and_reapprove_in_one_checkpoint_stays_active() {
+        // `process` emits the GuestRevoked values before the room's final participants
+        let batch = folded(vec![revoke(10), join(10)]);
+        let guest = &batch.participants[&(ROOM.to_string(), GUEST.to_string())];
+
+        assert!(guest.revocation.is_some());
+        assert_eq!(guest.active.as_ref().map(|p| p.checkpoint_sequence_number), Some(10));
+    }
+
+    #[test]
+    fn test_room_delete_drops_earlier_participants() {
+        let mut values = vec![join(10)];
+        values.push(ProcessedValue::RoomDelete { room_id: ROOM.to_string(), provenance: at(11) });
+        let batch = folded(values);
+
+        assert!(batch.participants.is_empty());
+        assert!(batch.deleted_rooms.contains_key(ROOM));
+    }
+
+    /// A HostCap held by `owner`, as a Move object at `version`
+    fn host_cap_object(packages: &SuimeetPackages, owner: SuiAddress, version: u64) -> Object {
+        use sui_indexer_alt_framework::types::base_types::{MoveObjectType, SequenceNumber};
+        use sui_indexer_alt_framework::types::digests::TransactionDigest;
+        use sui_indexer_alt_framework::types::object::MoveObject;
+
+        let cap_id = ObjectID::from_hex_literal("0xca9").unwrap();
+        let room_id = ObjectID::from_hex_literal(ROOM).unwrap();
+        let contents = [cap_id.into_bytes().to_vec(), room_id.into_bytes().to_vec(), 7u64.to_le_bytes().to_vec()].concat();
+
+        let type_ = MoveObjectType::from(packages.struct_tag("sealmeet", "HostCap").unwrap());
+        let move_object = unsafe {
+            MoveObject::new_from_execution_with_limit(type_, true, SequenceNumber::from_u64(version), contents, 256 * 1024)
+        }
+        .unwrap();
+
+        Object::new_move(move_object, Owner::AddressOwner(owner), TransactionDigest::ZERO)
+    }
+
+    #[test]
+    fn test_redecode_removed_host_cap_does_not_restore_owner() {
+        let packages = SuimeetPackages::new("0xc90c", &[]).unwrap();
+        let processor = RoomProcessor::new(packages.clone(), DecodeErrorPolicy::DeadLetter).unwrap();
+        let owner = SuiAddress::from_str("0xb0b").unwrap();
+        // The input version of the cap; the removing transaction wrote version 9
+        let cap = host_cap_object(&packages, owner, 5);
+
+        let letter = |state| {
+            DecodeErrorPolicy::DeadLetter
+                .object_letter(RoomProcessor::NAME, &cap, state, &at(9), anyhow::anyhow!("bad cap"))
+                .unwrap()
+                .recorded(1)
+        };
+
+        let deleted = processor.redecode(&letter(ObjectState::Deleted)).unwrap();
+        assert!(matches!(
+            &deleted[..],
+            [
+                ProcessedValue::HostCapChange { change_kind, previous_owner_address: Some(previous), owner_address: None, .. },
+                ProcessedValue::HostCapDelete { .. },
+            ] if change_kind == "DELETED" && *previous == owner.to_string()
+        ));
+
+        let wrapped = processor.redecode(&letter(ObjectState::Wrapped)).unwrap();
+        assert!(matches!(
+            wrapped.last(),
+            Some(ProcessedValue::HostCapUpsert { owner_kind, owner_address: None, .. }) if owner_kind == "WRAPPED"
+        ));
+    }
+
+    /// A stale revoke retried against the database at `TEST_DATABASE_URL`
+    #[cfg(feature = "integration-tests")]
+    #[tokio::test]
+    async fn test_retry_skips_revoke_older_than_approval() {
+        use move_core_types::identifier::Identifier;
+        use sui_indexer_alt_framework::postgres::{Db, DbArgs};
+        use sui_indexer_alt_framework::types::event::Event;
+
+        use crate::dead_letters::retry_dead_letters;
+        use crate::db::schema::indexer_dead_letters;
+        use crate::MIGRATIONS;
+
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL is set");
+        let db = Db::for_write(url.parse().unwrap(), DbArgs::default()).await.unwrap();
+        db.run_migrations(Some(&MIGRATIONS)).await.unwrap();
+        let mut conn = db.connect().await.unwrap();
+
+        let packages = SuimeetPackages::new("0xc90c", &[]).unwrap();
+        let processor = RoomProcessor::new(packages.clone(), DecodeErrorPolicy::DeadLetter).unwrap();
+        let room = ObjectID::random();
+        let guest = SuiAddress::random_for_testing_only();
+        let host = SuiAddress::random_for_testing_only();
+        let (room_id, guest_address) = (room.to_string(), guest.to_string());
+
+        // The guest joined the room at checkpoint 5 and was approved at checkpoint 20
+        let (joined, approved) = (at(5), at(20));
+        let values = vec![
+            ProcessedValue::RoomUpsert {
+                room_id: room_id.clone(),
+                title: "Standup".to_string(),
+                description: None,
+                hosts: vec![host.to_string()],
+                participants: vec![host.to_string(), guest_address.clone()],
+                seal_policy_id: ObjectID::random().to_string(),
+                status: 2,
+                max_participants: 10,
+                require_approval: true,
+                created_at: 0,
+                started_at: None,
+                ended_at: None,
+                provenance: joined.clone(),
+            },
+            ProcessedValue::ParticipantUpsert {
+                room_id: room_id.clone(),
+                participant_address: guest_address.clone(),
+                role: "PARTICIPANT".to_string(),
+                provenance: joined,
+            },
+            ProcessedValue::ParticipantApproved {
+                room_id: room_id.clone(),
+                participant_address: guest_address.clone(),
+                approved_by: host.to_string(),
+                provenance: approved,
+            },
+        ];
+        RoomProcessor::commit(&folded(values), &mut conn).await.unwrap();
+
+        // An earlier revocation of the same guest failed to decode at checkpoint 10
+        let revoke = Event {
+            package_id: ObjectID::from_hex_literal("0xc90c").unwrap(),
+            transaction_module: Identifier::new("sealmeet").unwrap(),
+            sender: host,
+            type_: packages.struct_tag("sealmeet", "GuestRevoked").unwrap(),
+            contents: [room.into_bytes().to_vec(), guest.to_vec(), host.to_vec()].concat(),
+        };
+        let revoked = Provenance {
+            transaction_digest: format!("stale-revoke-{room_id}"),
+            ..at(10)
+        };
+        let letter = DecodeErrorPolicy::DeadLetter
+            .event_letter(RoomProcessor::NAME, &revoke, 0, &revoked, anyhow::anyhow!("bad event"))
+            .unwrap();
+        insert_dead_letters(&[letter], &mut conn).await.unwrap();
+
+        retry_dead_letters(&processor, &db).await.unwrap();
+
+        let status: String = indexer_dead_letters::table
+            .filter(indexer_dead_letters::transaction_digest.eq(&revoked.transaction_digest))
+            .select(indexer_dead_letters::status)
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(status, "SUPERSEDED");
+
+        let (participant_status, approved_by, checkpoint): (String, Option<String>, i64) = room_participants::table
+            .filter(room_participants::room_id.eq(&room_id))
+            .filter(room_participants::participant_address.eq(&guest_address))
+            .select((
+                room_participants::status,
+                room_participants::approved_by,
+                room_participants::checkpoint_sequence_number,
+            ))
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(participant_status, "ACTIVE");
+        assert_eq!(approved_by, Some(host.to_string()));
+        assert_eq!(checkpoint, 20);
+
+        diesel::delete(meeting_rooms::table.filter(meeting_rooms::room_id.eq(&room_id)))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        diesel::delete(indexer_dead_letters::table.filter(indexer_dead_letters::transaction_digest.eq(&revoked.transaction_digest)))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+// Versioned Move struct decoders
+//
+// An upgrade cannot change the layout of an existing struct, so a struct decodes the same way
+// for every package version. A reshaped struct ships under a new name (say `MeetingRoomV2`)
+// whose type carries the address of the upgrade that defined it. A decoder maps each such
+// struct to its own decode function, into one indexer representation, so a single binary
+// reads old and new objects side by side.
+
+use move_core_types::language_storage::StructTag;
+use sui_indexer_alt_framework::types::base_types::ObjectID;
+use sui_indexer_alt_framework::types::object::Object;
+use anyhow::Result;
+
+use super::SuimeetPackages;
+
+/// Decodes one struct into the indexer's representation
+pub type DecodeFn<T> = fn(&Object) -> Result<T>;
+
+/// The structs of one SuiMeet module that decode into `T`
+pub struct VersionedDecoder<T> {
+    packages: SuimeetPackages,
+    module: &'static str,
+    /// (package version that defined the struct, struct name) -> decoder for it
+    layouts: Vec<(i64, &'static str, DecodeFn<T>)>,
+}
+
+impl<T> VersionedDecoder<T> {
+    pub fn new(packages: SuimeetPackages, module: &'static str) -> Self {
+        Self {
+            packages,
+            module,
+            layouts: Vec::new(),
+        }
+    }
+
+    /// Decode struct `name`, first defined by package version `defined_in`, with `decode`
+    pub fn layout(mut self, defined_in: i64, name: &'static str, decode: DecodeFn<T>) -> Self {
+        self.layouts.push((defined_in, name, decode));
+        self
+    }
+
+    /// Decoder for objects of `type_`, if it is one of the registered structs
+    fn decoder_for(&self, type_: &StructTag) -> Option<DecodeFn<T>> {
+        if type_.module.as_str() != self.module {
+            return None;
+        }
+
+        // A struct keeps the address of the version that defined it
+        let version = self.packages.version_of(&ObjectID::from(type_.address))?;
+        self.layouts
+            .iter()
+            .find(|(defined_in, name, _)| *defined_in == version && *name == type_.name.as_str())
+            .map(|(_, _, decode)| *decode)
+    }
+
+    /// Whether objects of `type_` are decoded by this decoder
+    pub fn decodes(&self, type_: &StructTag) -> bool {
+        self.decoder_for(type_).is_some()
+    }
+
+    /// Decode `object`, or `None` if its type is not one of the registered structs
+    pub fn decode(&self, object: &Object) -> Result<Option<T>> {
+        let Some(type_) = object.type_() else {
+            return Ok(None);
+        };
+
+        match self.decoder_for(&StructTag::from(type_.clone())) {
+            Some(decode) => decode(object).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sui_indexer_alt_framework::types::base_types::{MoveObjectType, SequenceNumber};
+    use sui_indexer_alt_framework::types::digests::TransactionDigest;
+    use sui_indexer_alt_framework::types::object::MoveObject;
+    use sui_indexer_alt_framework::types::parse_sui_struct_tag;
+    use sui_types::object::Owner;
+
+    const ORIGINAL: &str = "0xc90c";
+    const UPGRADE: &str = "0xa1";
+
+    fn v1(_: &Object) -> Result<&'static str> {
+        Ok("MeetingRoom")
+    }
+
+    fn v2(_: &Object) -> Result<&'static str> {
+        Ok("MeetingRoomV2")
+    }
+
+    fn decoder() -> VersionedDecoder<&'static str> {
+        let packages = SuimeetPackages::new(ORIGINAL, &[UPGRADE.to_string()]).unwrap();
+        VersionedDecoder::new(packages, "sealmeet")
+            .layout(1, "MeetingRoom", v1)
+            .layout(2, "MeetingRoomV2", v2)
+    }
+
+    fn tag(package: &str, name: &str) -> StructTag {
+        parse_sui_struct_tag(&format!("{package}::sealmeet::{name}")).unwrap()
+    }
+
+    /// An object of `type_` holding only its ID
+    fn object(type_: StructTag) -> Object {
+        let move_object = unsafe {
+            MoveObject::new_from_execution_with_limit(
+                MoveObjectType::from(type_),
+                true,
+                SequenceNumber::from_u64(1),
+                ObjectID::ZERO.into_bytes().to_vec(),
+                256 * 1024,
+            )
+        }
+        .unwrap();
+
+        Object::new_move(move_object, Owner::Immutable, TransactionDigest::ZERO)
+    }
+
+    #[test]
+    fn test_struct_dispatches_by_defining_version() {
+        let decoder = decoder();
+
+        assert_eq!(decoder.decode(&object(tag(ORIGINAL, "MeetingRoom"))).unwrap(), Some("MeetingRoom"));
+        assert_eq!(decoder.decode(&object(tag(UPGRADE, "MeetingRoomV2"))).unwrap(), Some("MeetingRoomV2"));
+
+        // Each struct only exists at the address of the version that defined it
+        assert!(!decoder.decodes(&tag(UPGRADE, "MeetingRoom")));
+        assert!(!decoder.decodes(&tag(ORIGINAL, "MeetingRoomV2")));
+        assert!(!decoder.decodes(&tag("0xb0b", "MeetingRoom")));
+        assert_eq!(decoder.decode(&object(tag(ORIGINAL, "HostCap"))).unwrap(), None);
+    }
+}
@@ -3,10 +3,11 @@
 // Extracts fields from HostCap Move struct on-chain
 
 use anyhow::{Context, Result};
-use move_core_types::language_storage::StructTag;
 use sui_indexer_alt_framework::types::object::Object;
 use sui_indexer_alt_framework::types::base_types::ObjectID;
+use sui_types::object::Owner;
 use crate::models::sealmeet::sealmeet::HostCap;
+use super::{SuimeetPackages, VersionedDecoder};
 
 /// Parsed HostCap fields extracted from Move object
 #[derive(Debug, Clone)]
@@ -14,22 +15,26 @@ pub struct ParsedHostCap {
     pub cap_id: ObjectID,
     pub room_id: ObjectID,
     pub granted_at: u64,
+    pub owner_kind: &'static str,
+    pub owner_address: Option<String>,
 }
 
-/// Extract HostCap fields from a Move object
+/// Every struct decoded as a HostCap, keyed by the package version that defined it
+pub fn host_cap_decoder(packages: &SuimeetPackages) -> VersionedDecoder<ParsedHostCap> {
+    VersionedDecoder::new(packages.clone(), "sealmeet")
+        .layout(1, "HostCap", decode_host_cap)
+}
+
+/// Extract HostCap fields from a Move object of any struct known to `decoder`
 pub fn extract_host_cap(
-    host_cap_type: &StructTag,
+    decoder: &VersionedDecoder<ParsedHostCap>,
     object: &Object,
 ) -> Result<Option<ParsedHostCap>> {
-    // Check if this object is a HostCap type
-    let Some(type_) = object.type_() else {
-        return Ok(None);
-    };
-
-    if !type_.is(host_cap_type) {
-        return Ok(None);
-    }
+    decoder.decode(object)
+}
 
+/// The original publish's `HostCap`, whose layout upgrades keep
+fn decode_host_cap(object: &Object) -> Result<ParsedHostCap> {
     let move_object = object
         .data
         .try_as_move()
@@ -47,9 +52,27 @@ pub fn extract_host_cap(
     // Convert ObjectId to ObjectID
     let room_id = ObjectID::from(move_types::Address::from(fields.room_id.0));
 
-    Ok(Some(ParsedHostCap {
+    let (owner_kind, owner_address) = owner_parts(&object.owner);
+
+    Ok(ParsedHostCap {
         cap_id: object.id(),
         room_id,
         granted_at: fields.granted_at,
-    }))
+        owner_kind,
+        owner_address,
+    })
+}
+
+/// Split an object `Owner` into a kind label and the owning address (if any)
+///
+/// For `ObjectOwner` the address is the parent object's ID, e.g. when a HostCap is
+/// wrapped in or attached to another object.
+pub fn owner_parts(owner: &Owner) -> (&'static str, Option<String>) {
+    match owner {
+        Owner::AddressOwner(addr) => ("ADDRESS", Some(addr.to_string())),
+        Owner::ObjectOwner(addr) => ("OBJECT", Some(addr.to_string())),
+        Owner::ConsensusAddressOwner { owner, .. } => ("ADDRESS", Some(owner.to_string())),
+        Owner::Shared { .. } => ("SHARED", None),
+        Owner::Immutable => ("IMMUTABLE", None),
+    }
 }
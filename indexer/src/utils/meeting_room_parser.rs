@@ -3,7 +3,6 @@
 // Extracts fields from MeetingRoom Move struct on-chain
 
 use anyhow::{Context, Result};
-use move_core_types::language_storage::StructTag;
 use sui_indexer_alt_framework::types::object::Object;
 use sui_indexer_alt_framework::types::base_types::{ObjectID, SuiAddress, SequenceNumber};
 use sui_indexer_alt_framework::types::effects::TransactionEffectsAPI;
@@ -11,6 +10,7 @@ use sui_indexer_alt_framework::types::full_checkpoint_content::CheckpointData;
 use std::collections::{BTreeMap, HashSet};
 use std::collections::btree_map::Entry;
 use crate::models::sealmeet::sealmeet::MeetingRoom;
+use super::{SuimeetPackages, VersionedDecoder};
 
 /// Parsed MeetingRoom fields extracted from Move object
 #[derive(Debug, Clone)]
@@ -23,26 +23,32 @@ pub struct ParsedMeetingRoom {
     pub max_participants: u64,
     pub require_approval: bool,
     pub seal_policy_id: ObjectID,
+    pub whitelist: Vec<SuiAddress>,
+    /// Epoch at creation, clock timestamp (ms) after the first whitelist change
+    pub whitelist_updated_at: u64,
     pub status: u8,
     pub created_at: u64,
     pub started_at: u64,
     pub ended_at: u64,
 }
 
-/// Extract MeetingRoom fields from a Move object
+/// Every struct decoded as a MeetingRoom, keyed by the package version that defined it
+pub fn meeting_room_decoder(packages: &SuimeetPackages) -> VersionedDecoder<ParsedMeetingRoom> {
+    VersionedDecoder::new(packages.clone(), "sealmeet")
+        .layout(1, "MeetingRoom", decode_meeting_room)
+}
+
+/// Extract MeetingRoom fields from a Move object of any struct known to `decoder`
 pub fn extract_meeting_room(
-    meeting_room_type: &StructTag,
+    decoder: &VersionedDecoder<ParsedMeetingRoom>,
     object: &Object,
 ) -> Result<Option<ParsedMeetingRoom>> {
-    // Check if this object is a MeetingRoom type
-    let Some(type_) = object.type_() else {
-        return Ok(None);
-    };
-
-    if !type_.is(meeting_room_type) {
-        return Ok(None);
-    }
+    decoder.decode(object)
+}
 
+/// The original publish's `MeetingRoom`: upgrades keep both its address and its layout, so
+/// the generated binding decodes MeetingRooms of every package version
+fn decode_meeting_room(object: &Object) -> Result<ParsedMeetingRoom> {
     let move_object = object
         .data
         .try_as_move()
@@ -51,8 +57,7 @@ pub fn extract_meeting_room(
     // Deserialize the MeetingRoom struct
     let fields: MeetingRoom = bcs::from_bytes(move_object.contents())
         .context(format!(
-            "Failed to deserialize MeetingRoom struct. Object ID: {:?}, Version: {:?}, Contents length: {} bytes. \
-             This usually means the Move contract struct layout changed.",
+            "Failed to deserialize MeetingRoom struct. Object ID: {:?}, Version: {:?}, Contents length: {} bytes",
             object.id(),
             object.version(),
             move_object.contents().len()
@@ -76,7 +81,13 @@ pub fn extract_meeting_room(
     // Convert ObjectId to ObjectID - ObjectId contains inner bytes
     let seal_policy_id = ObjectID::from(move_types::Address::from(fields.seal_policy_id.0));
 
-    Ok(Some(ParsedMeetingRoom {
+    // The wrapped SealApproveWhitelist gates Seal decryption
+    let whitelist: Vec<SuiAddress> = fields.seal_policy.whitelist
+        .into_iter()
+        .map(|addr| SuiAddress::from(addr))
+        .collect();
+
+    Ok(ParsedMeetingRoom {
         object_id: object.id(),
         title,
         description,
@@ -85,11 +96,13 @@ pub fn extract_meeting_room(
         max_participants: fields.max_participants,
         require_approval: fields.require_approval,
         seal_policy_id,
+        whitelist,
+        whitelist_updated_at: fields.seal_policy.updated_at,
         status: fields.status,
         created_at: fields.created_at,
         started_at: fields.started_at,
         ended_at: fields.ended_at,
-    }))
+    })
 }
 
 /// Returns the first appearance of all objects that were used as inputs to the transactions in the
@@ -181,3 +194,48 @@ pub fn checkpoint_output_objects(
 
     Ok(output_objects)
 }
+
+/// Compare a room's participants with its Seal whitelist
+///
+/// Returns `(missing, extra)`: participants absent from the whitelist, and whitelisted
+/// addresses that are not participants. Both empty means the two lists agree.
+pub fn whitelist_drift(participants: &[String], whitelist: &[String]) -> (Vec<String>, Vec<String>) {
+    let participant_set: HashSet<&String> = participants.iter().collect();
+    let whitelist_set: HashSet<&String> = whitelist.iter().collect();
+
+    let missing = participants
+        .iter()
+        .filter(|addr| !whitelist_set.contains(addr))
+        .cloned()
+        .collect();
+    let extra = whitelist
+        .iter()
+        .filter(|addr| !participant_set.contains(addr))
+        .cloned()
+        .collect();
+
+    (missing, extra)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addrs(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_whitelist_drift_in_sync() {
+        let (missing, extra) = whitelist_drift(&addrs(&["0x1", "0x2"]), &addrs(&["0x2", "0x1"]));
+        assert!(missing.is_empty());
+        assert!(extra.is_empty());
+    }
+
+    #[test]
+    fn test_whitelist_drift_both_directions() {
+        let (missing, extra) = whitelist_drift(&addrs(&["0x1", "0x2"]), &addrs(&["0x2", "0x3"]));
+        assert_eq!(missing, addrs(&["0x1"]));
+        assert_eq!(extra, addrs(&["0x3"]));
+    }
+}
@@ -3,9 +3,10 @@
 // Extracts MeetingMetadata from dynamic fields on MeetingRoom objects
 
 use anyhow::{Context, Result};
-use move_core_types::language_storage::StructTag;
+use move_core_types::language_storage::{StructTag, TypeTag};
 use sui_indexer_alt_framework::types::object::Object;
 use sui_indexer_alt_framework::types::base_types::ObjectID;
+use sui_indexer_alt_framework::types::SUI_FRAMEWORK_ADDRESS;
 use crate::models::sealmeet::sealmeet::MeetingMetadata;
 use crate::utils::BlobId;
 
@@ -22,6 +23,8 @@ pub struct ParsedMeetingMetadata {
 
 /// Extract MeetingMetadata from a dynamic field object
 /// Dynamic fields in Sui are stored as separate objects with type DynamicField<K, V>
+///
+/// `metadata_type` is `MeetingMetadata` of the original publish, which every upgrade keeps.
 pub fn extract_meeting_metadata(
     metadata_type: &StructTag,
     object: &Object,
@@ -32,7 +35,23 @@ pub fn extract_meeting_metadata(
         return Ok(None);
     };
 
-    if !type_.is(metadata_type) {
+    let tag = StructTag::from(type_.clone());
+    if tag.address != SUI_FRAMEWORK_ADDRESS
+        || tag.module.as_str() != "dynamic_field"
+        || tag.name.as_str() != "Field"
+    {
+        return Ok(None);
+    }
+
+    let [TypeTag::Vector(key), TypeTag::Struct(value)] = tag.type_params.as_slice() else {
+        return Ok(None);
+    };
+
+    if **key != TypeTag::U8 {
+        return Ok(None);
+    }
+
+    if **value != *metadata_type {
         return Ok(None);
     }
 
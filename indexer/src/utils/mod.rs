@@ -4,11 +4,19 @@
 //! Utility functions for type conversions and common operations
 
 pub mod types;
+pub mod decoders;
 pub mod meeting_room_parser;
 pub mod host_cap_parser;
 pub mod metadata_parser;
+pub mod packages;
+pub mod provenance;
+pub mod registry_parser;
 
 pub use types::*;
+pub use decoders::*;
 pub use meeting_room_parser::*;
 pub use host_cap_parser::*;
 pub use metadata_parser::*;
+pub use packages::*;
+pub use provenance::*;
+pub use registry_parser::*;
@@ -0,0 +1,120 @@
+// SuiMeet package ids
+//
+// Tracks the original package id and every upgrade of it. Struct types keep the address
+// of the package version that first defined them (the original id for everything shipped
+// in the first publish), while code, and therefore events' `package_id`, runs from the
+// upgraded ids.
+
+use std::collections::BTreeMap;
+use anyhow::{bail, Result};
+use move_core_types::language_storage::StructTag;
+use sui_indexer_alt_framework::types::base_types::ObjectID;
+use sui_indexer_alt_framework::types::full_checkpoint_content::CheckpointTransaction;
+use sui_indexer_alt_framework::types::parse_sui_struct_tag;
+use sui_indexer_alt_framework::types::transaction::TransactionDataAPI;
+
+/// The original SuiMeet package and all of its known upgrades
+#[derive(Debug, Clone)]
+pub struct SuimeetPackages {
+    original_id: ObjectID,
+    /// Package id -> package version, 1 being the original publish
+    versions: BTreeMap<ObjectID, i64>,
+}
+
+impl SuimeetPackages {
+    /// `upgraded_ids` must be listed in upgrade order, so the n-th entry is version n + 1
+    pub fn new(original_id: &str, upgraded_ids: &[String]) -> Result<Self> {
+        let original_id = ObjectID::from_hex_literal(original_id)?;
+
+        let mut versions = BTreeMap::new();
+        versions.insert(original_id, 1);
+
+        for (index, upgraded_id) in upgraded_ids.iter().enumerate() {
+            let upgraded_id = ObjectID::from_hex_literal(upgraded_id)?;
+            if versions.insert(upgraded_id, index as i64 + 2).is_some() {
+                bail!("SuiMeet package id {} is listed more than once", upgraded_id);
+            }
+        }
+
+        Ok(Self { original_id, versions })
+    }
+
+    pub fn original_id(&self) -> ObjectID {
+        self.original_id
+    }
+
+    /// Type tag of a struct defined in the original publish, e.g. `("sealmeet", "MeetingRoom")`
+    pub fn struct_tag(&self, module: &str, name: &str) -> Result<StructTag> {
+        Ok(parse_sui_struct_tag(&format!(
+            "{}::{}::{}",
+            self.original_id.to_hex_literal(),
+            module,
+            name
+        ))?)
+    }
+
+    /// The original package id and every upgrade, in no particular order
+    pub fn ids(&self) -> impl Iterator<Item = ObjectID> + '_ {
+        self.versions.keys().copied()
+    }
+
+    /// Whether `package_id` is the original package or one of its upgrades
+    pub fn contains(&self, package_id: &ObjectID) -> bool {
+        self.versions.contains_key(package_id)
+    }
+
+    /// Whether `type_` is a struct defined by any version of the package
+    pub fn defines(&self, type_: &StructTag) -> bool {
+        self.contains(&ObjectID::from(type_.address))
+    }
+
+    /// Version of `package_id`, if it is one of ours
+    pub fn version_of(&self, package_id: &ObjectID) -> Option<i64> {
+        self.versions.get(package_id).copied()
+    }
+
+    /// Highest package version whose code `tx` called, if it called ours at all
+    pub fn version_called_by(&self, tx: &CheckpointTransaction) -> Option<i64> {
+        tx.transaction
+            .transaction_data()
+            .move_calls()
+            .into_iter()
+            .filter_map(|(package_id, _, _)| self.version_of(package_id))
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "0xc90c";
+    const UPGRADE_1: &str = "0xa1";
+    const UPGRADE_2: &str = "0xa2";
+
+    #[test]
+    fn test_versions_follow_upgrade_order() {
+        let packages =
+            SuimeetPackages::new(ORIGINAL, &[UPGRADE_1.to_string(), UPGRADE_2.to_string()]).unwrap();
+
+        let id = |hex: &str| ObjectID::from_hex_literal(hex).unwrap();
+        assert_eq!(packages.version_of(&id(ORIGINAL)), Some(1));
+        assert_eq!(packages.version_of(&id(UPGRADE_1)), Some(2));
+        assert_eq!(packages.version_of(&id(UPGRADE_2)), Some(3));
+        assert_eq!(packages.version_of(&id("0xdead")), None);
+    }
+
+    #[test]
+    fn test_types_keep_original_address() {
+        let packages = SuimeetPackages::new(ORIGINAL, &[UPGRADE_1.to_string()]).unwrap();
+        let room_type = packages.struct_tag("sealmeet", "MeetingRoom").unwrap();
+
+        assert_eq!(ObjectID::from(room_type.address), packages.original_id());
+        assert!(packages.defines(&room_type));
+    }
+
+    #[test]
+    fn test_duplicate_package_id_rejected() {
+        assert!(SuimeetPackages::new(ORIGINAL, &[ORIGINAL.to_string()]).is_err());
+    }
+}
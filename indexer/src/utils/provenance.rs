@@ -0,0 +1,132 @@
+// Checkpoint / transaction provenance
+//
+// Records which checkpoint, transaction and object version produced an indexed change
+
+use std::collections::{BTreeMap, BTreeSet};
+use chrono::{DateTime, NaiveDateTime};
+use sui_indexer_alt_framework::types::base_types::{ObjectID, SequenceNumber};
+use sui_indexer_alt_framework::types::digests::TransactionDigest;
+use sui_indexer_alt_framework::types::effects::{IDOperation, TransactionEffectsAPI};
+use sui_indexer_alt_framework::types::full_checkpoint_content::{CheckpointData, CheckpointTransaction};
+
+use super::SuimeetPackages;
+
+/// On-chain origin of a single indexed change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    pub checkpoint_sequence_number: i64,
+    pub checkpoint_timestamp_ms: i64,
+    pub transaction_digest: String,
+    pub object_version: i64,
+    /// SuiMeet package version whose code the transaction called, if any
+    pub package_version: Option<i64>,
+}
+
+impl Provenance {
+    /// Checkpoint timestamp as a UTC `TIMESTAMP` value
+    pub fn checkpoint_time(&self) -> NaiveDateTime {
+        DateTime::from_timestamp_millis(self.checkpoint_timestamp_ms)
+            .unwrap_or_default()
+            .naive_utc()
+    }
+}
+
+/// Provenance lookup for all objects touched in a checkpoint
+///
+/// Objects are mapped to the last transaction in the checkpoint that changed them, so the
+/// digest and version always describe the final state seen by the processors.
+pub struct CheckpointProvenance {
+    checkpoint_sequence_number: i64,
+    checkpoint_timestamp_ms: i64,
+    object_changes: BTreeMap<ObjectID, (TransactionDigest, SequenceNumber, Option<i64>)>,
+    packages: SuimeetPackages,
+    created: BTreeSet<ObjectID>,
+    deleted: BTreeSet<ObjectID>,
+}
+
+impl CheckpointProvenance {
+    pub fn new(checkpoint: &CheckpointData, packages: &SuimeetPackages) -> Self {
+        let mut object_changes = BTreeMap::new();
+        let mut created = BTreeSet::new();
+        let mut deleted = BTreeSet::new();
+
+        for tx in &checkpoint.transactions {
+            let digest = *tx.effects.transaction_digest();
+            let lamport_version = tx.effects.lamport_version();
+            let package_version = packages.version_called_by(tx);
+
+            for change in tx.effects.object_changes() {
+                // Deleted and wrapped objects have no output version, the transaction's
+                // lamport version is the version they were consumed at.
+                let version = change.output_version.unwrap_or(lamport_version);
+                object_changes.insert(change.id, (digest, version, package_version));
+
+                match change.id_operation {
+                    IDOperation::Created => {
+                        created.insert(change.id);
+                    }
+                    IDOperation::Deleted => {
+                        deleted.insert(change.id);
+                    }
+                    IDOperation::None => {}
+                }
+            }
+        }
+
+        Self {
+            checkpoint_sequence_number: checkpoint.checkpoint_summary.sequence_number as i64,
+            checkpoint_timestamp_ms: checkpoint.checkpoint_summary.timestamp_ms as i64,
+            object_changes,
+            packages: packages.clone(),
+            created,
+            deleted,
+        }
+    }
+
+    /// Whether `object_id` was created (as opposed to unwrapped) in this checkpoint
+    pub fn was_created(&self, object_id: &ObjectID) -> bool {
+        self.created.contains(object_id)
+    }
+
+    /// Whether `object_id` was deleted (as opposed to wrapped) in this checkpoint
+    pub fn was_deleted(&self, object_id: &ObjectID) -> bool {
+        self.deleted.contains(object_id)
+    }
+
+    pub fn checkpoint_sequence_number(&self) -> i64 {
+        self.checkpoint_sequence_number
+    }
+
+    pub fn checkpoint_timestamp_ms(&self) -> i64 {
+        self.checkpoint_timestamp_ms
+    }
+
+    /// Provenance of the last change to `object_id` in this checkpoint
+    pub fn for_object(&self, object_id: &ObjectID) -> Provenance {
+        let (transaction_digest, object_version, package_version) = match self.object_changes.get(object_id) {
+            Some((digest, version, package_version)) => {
+                (digest.to_string(), version.value() as i64, *package_version)
+            }
+            None => (String::new(), 0, None),
+        };
+
+        Provenance {
+            checkpoint_sequence_number: self.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: self.checkpoint_timestamp_ms,
+            transaction_digest,
+            object_version,
+            package_version,
+        }
+    }
+
+    /// Provenance of a change caused by `tx` (e.g. an event it emitted)
+    pub fn for_transaction(&self, tx: &CheckpointTransaction) -> Provenance {
+        Provenance {
+            checkpoint_sequence_number: self.checkpoint_sequence_number,
+            checkpoint_timestamp_ms: self.checkpoint_timestamp_ms,
+            transaction_digest: tx.effects.transaction_digest().to_string(),
+            object_version: tx.effects.lamport_version().value() as i64,
+            package_version: self.packages.version_called_by(tx),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+// RoomRegistry Move object parser
+//
+// Extracts the shared RoomRegistry and the entries of its Table dynamic fields
+
+use anyhow::{Context, Result};
+use move_core_types::language_storage::StructTag;
+use sui_indexer_alt_framework::types::object::Object;
+use sui_indexer_alt_framework::types::base_types::{ObjectID, SuiAddress};
+use crate::models::sealmeet::sealmeet::RoomRegistry;
+
+/// Parsed RoomRegistry fields extracted from Move object
+#[derive(Debug, Clone)]
+pub struct ParsedRoomRegistry {
+    pub registry_id: ObjectID,
+    pub room_count: u64,
+    pub all_rooms: Vec<ObjectID>,
+    pub active_rooms: Vec<ObjectID>,
+    pub scheduled_rooms: Vec<ObjectID>,
+    /// Object ID of the `rooms_by_host` Table, parent of its dynamic fields
+    pub rooms_by_host_table_id: ObjectID,
+    /// Object ID of the `rooms_by_seal_id` Table, parent of its dynamic fields
+    pub rooms_by_seal_id_table_id: ObjectID,
+}
+
+/// Entry of `rooms_by_host: Table<address, vector<ID>>`
+#[derive(Debug, Clone)]
+pub struct ParsedHostRooms {
+    pub dynamic_field_id: ObjectID,
+    pub table_id: ObjectID,
+    pub host: SuiAddress,
+    pub room_ids: Vec<ObjectID>,
+}
+
+/// Entry of `rooms_by_seal_id: Table<ID, ID>`
+#[derive(Debug, Clone)]
+pub struct ParsedSealRoom {
+    pub dynamic_field_id: ObjectID,
+    pub table_id: ObjectID,
+    pub seal_policy_id: ObjectID,
+    pub room_id: ObjectID,
+}
+
+fn to_object_id(id: move_types::ObjectId) -> ObjectID {
+    ObjectID::from(move_types::Address::from(id.0))
+}
+
+/// Extract RoomRegistry fields from a Move object
+pub fn extract_room_registry(
+    registry_type: &StructTag,
+    object: &Object,
+) -> Result<Option<ParsedRoomRegistry>> {
+    let Some(type_) = object.type_() else {
+        return Ok(None);
+    };
+
+    if !type_.is(registry_type) {
+        return Ok(None);
+    }
+
+    let move_object = object
+        .data
+        .try_as_move()
+        .ok_or_else(|| anyhow::anyhow!("Not a Move object"))?;
+
+    let fields: RoomRegistry = bcs::from_bytes(move_object.contents())
+        .context(format!(
+            "Failed to deserialize RoomRegistry struct. Object ID: {:?}, Version: {:?}, Contents length: {} bytes",
+            object.id(),
+            object.version(),
+            move_object.contents().len()
+        ))?;
+
+    Ok(Some(ParsedRoomRegistry {
+        registry_id: object.id(),
+        room_count: fields.room_count,
+        all_rooms: fields.all_rooms.into_iter().map(to_object_id).collect(),
+        active_rooms: fields.active_rooms.into_iter().map(to_object_id).collect(),
+        scheduled_rooms: fields.scheduled_rooms.into_iter().map(to_object_id).collect(),
+        rooms_by_host_table_id: to_object_id(fields.rooms_by_host.id),
+        rooms_by_seal_id_table_id: to_object_id(fields.rooms_by_seal_id.id),
+    }))
+}
+
+/// Extract a `rooms_by_host` entry from a `Field<address, vector<ID>>` dynamic field
+///
+/// The type is generic, so callers must check `table_id` against a known registry table.
+pub fn extract_host_rooms(
+    field_type: &StructTag,
+    object: &Object,
+    table_id: ObjectID,
+) -> Result<Option<ParsedHostRooms>> {
+    let Some(type_) = object.type_() else {
+        return Ok(None);
+    };
+
+    if !type_.is(field_type) {
+        return Ok(None);
+    }
+
+    let move_object = object
+        .data
+        .try_as_move()
+        .ok_or_else(|| anyhow::anyhow!("Not a Move object"))?;
+
+    #[derive(serde::Deserialize)]
+    struct DynamicFieldWrapper {
+        #[allow(dead_code)]
+        id: move_types::ObjectId,
+        name: move_types::Address,
+        value: Vec<move_types::ObjectId>,
+    }
+
+    let wrapper: DynamicFieldWrapper = bcs::from_bytes(move_object.contents())
+        .context(format!(
+            "Failed to deserialize DynamicField<address, vector<ID>>. Object ID: {:?}, Version: {:?}",
+            object.id(),
+            object.version()
+        ))?;
+
+    Ok(Some(ParsedHostRooms {
+        dynamic_field_id: object.id(),
+        table_id,
+        host: SuiAddress::from(wrapper.name),
+        room_ids: wrapper.value.into_iter().map(to_object_id).collect(),
+    }))
+}
+
+/// Extract a `rooms_by_seal_id` entry from a `Field<ID, ID>` dynamic field
+///
+/// The type is generic, so callers must check `table_id` against a known registry table.
+pub fn extract_seal_room(
+    field_type: &StructTag,
+    object: &Object,
+    table_id: ObjectID,
+) -> Result<Option<ParsedSealRoom>> {
+    let Some(type_) = object.type_() else {
+        return Ok(None);
+    };
+
+    if !type_.is(field_type) {
+        return Ok(None);
+    }
+
+    let move_object = object
+        .data
+        .try_as_move()
+        .ok_or_else(|| anyhow::anyhow!("Not a Move object"))?;
+
+    #[derive(serde::Deserialize)]
+    struct DynamicFieldWrapper {
+        #[allow(dead_code)]
+        id: move_types::ObjectId,
+        name: move_types::ObjectId,
+        value: move_types::ObjectId,
+    }
+
+    let wrapper: DynamicFieldWrapper = bcs::from_bytes(move_object.contents())
+        .context(format!(
+            "Failed to deserialize DynamicField<ID, ID>. Object ID: {:?}, Version: {:?}",
+            object.id(),
+            object.version()
+        ))?;
+
+    Ok(Some(ParsedSealRoom {
+        dynamic_field_id: object.id(),
+        table_id,
+        seal_policy_id: to_object_id(wrapper.name),
+        room_id: to_object_id(wrapper.value),
+    }))
+}
@@ -0,0 +1,653 @@
+// Outbound webhooks - room lifecycle events POSTed to subscribed endpoints
+//
+// Subscriptions live in `webhook_subscriptions`, each for one host address or one room. The
+// event pipeline writes a `webhook_deliveries` row per matching subscription in the same
+// transaction that first writes the event, so an event is queued exactly when it is committed.
+// `--deliver-webhooks` drains that outbox: it claims due rows with `SKIP LOCKED`, so several
+// workers can run side by side, and retries failures with exponential backoff.
+//
+// Delivery is at-least-once: a worker that dies mid-request leaves its claim to expire and the
+// delivery is sent again. Receivers deduplicate by `delivery_id`.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use diesel::dsl::IntervalDsl;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Double, Int4, Int8, Jsonb, Text};
+use diesel_async::RunQueryDsl;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use sui_indexer_alt_framework::postgres::{self, Db};
+use tracing::{info, warn};
+
+use crate::db::schema::webhook_deliveries;
+
+/// Event types delivered to webhooks
+pub const EVENT_TYPES: [&str; 5] = ["RoomCreated", "RoomStarted", "RoomEnded", "GuestApproved", "GuestRevoked"];
+
+pub const EVENT_HEADER: &str = "X-SuiMeet-Event";
+pub const DELIVERY_HEADER: &str = "X-SuiMeet-Delivery";
+pub const TIMESTAMP_HEADER: &str = "X-SuiMeet-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-SuiMeet-Signature";
+
+/// Deliveries claimed per round
+const CLAIM_BATCH_SIZE: i64 = 100;
+/// Delay before the first retry, doubled for each later one
+const BASE_BACKOFF: Duration = Duration::from_secs(10);
+const MAX_BACKOFF: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Queue a delivery of each given event (by transaction digest and event index) to every
+/// active subscription matching it. A room's hosts are its current authority: the addresses
+/// with an ACTIVE HOST or DELEGATED_HOST row in `room_participants`, which the room pipeline
+/// keeps in line with the room's `hosts` and the owners of its HostCaps. That pipeline commits
+/// on its own schedule, so a RoomCreated event also goes to the creator it names, in case the
+/// room is not indexed yet. A subscription only receives events from after it was created, so
+/// one added while the indexer backfills is not flooded with history.
+const ENQUEUE_SQL: &str = "
+    INSERT INTO webhook_deliveries (
+        subscription_id, event_type, room_id, data,
+        checkpoint_sequence_number, checkpoint_timestamp_ms, transaction_digest, event_index)
+    SELECT s.id, e.event_type, e.room_id, e.data,
+        e.checkpoint_sequence_number, e.checkpoint_timestamp_ms, e.transaction_digest, e.event_index
+    FROM unnest($1::TEXT[], $2::BIGINT[]) AS k(transaction_digest, event_index)
+    JOIN room_events e
+        ON e.transaction_digest = k.transaction_digest AND e.event_index = k.event_index
+    JOIN webhook_subscriptions s
+        ON s.active
+        AND e.checkpoint_timestamp_ms >= (extract(epoch FROM s.created_at) * 1000)::BIGINT
+        AND (cardinality(s.event_types) = 0 OR e.event_type = ANY(s.event_types))
+        AND (s.room_id = e.room_id
+            OR (e.event_type = 'RoomCreated' AND s.host_address = e.data->>'host')
+            OR EXISTS (
+                SELECT 1 FROM room_participants h
+                WHERE h.room_id = e.room_id
+                  AND h.participant_address = s.host_address
+                  AND h.status = 'ACTIVE'
+                  AND h.role IN ('HOST', 'DELEGATED_HOST')))
+    ORDER BY e.checkpoint_sequence_number, e.transaction_digest, e.event_index, s.id
+    ON CONFLICT (subscription_id, transaction_digest, event_index) DO NOTHING";
+
+/// Claim due deliveries of active subscriptions with attempts left. The claim counts as an
+/// attempt and pushes `next_attempt_at` past the request timeout, so no other worker picks the
+/// row up meanwhile.
+const CLAIM_SQL: &str = "
+    UPDATE webhook_deliveries d
+    SET attempts = d.attempts + 1,
+        next_attempt_at = NOW() + make_interval(secs => $2)
+    FROM webhook_subscriptions s
+    WHERE s.id = d.subscription_id
+      AND d.id IN (
+        SELECT due.id
+        FROM webhook_deliveries due
+        JOIN webhook_subscriptions sub ON sub.id = due.subscription_id AND sub.active
+        WHERE due.status = 'PENDING' AND due.next_attempt_at <= NOW() AND due.attempts < $3
+        ORDER BY due.next_attempt_at, due.id
+        LIMIT $1
+        FOR UPDATE OF due SKIP LOCKED)
+    RETURNING d.id, d.event_type, d.room_id, d.data, d.attempts,
+        d.checkpoint_sequence_number, d.checkpoint_timestamp_ms, d.transaction_digest, d.event_index,
+        s.url, s.secret";
+
+/// Fail pending deliveries whose last attempt was claimed but never recorded, once that claim
+/// has expired. Without this, a worker dying mid-request would leave them pending for good.
+const FAIL_EXHAUSTED_SQL: &str = "
+    UPDATE webhook_deliveries
+    SET status = 'FAILED',
+        last_status_code = NULL,
+        last_error = 'no outcome recorded for the last attempt'
+    WHERE status = 'PENDING' AND attempts >= $1 AND next_attempt_at <= NOW()";
+
+/// Queue webhook deliveries for newly written events, given by (transaction digest, event
+/// index). Call in the commit that writes them, after the `room_events` insert.
+pub async fn enqueue_deliveries(
+    events: Vec<(String, i64)>,
+    conn: &mut postgres::Connection<'_>,
+) -> Result<usize> {
+    if events.is_empty() {
+        return Ok(0);
+    }
+
+    let (digests, indexes): (Vec<String>, Vec<i64>) = events.into_iter().unzip();
+
+    Ok(diesel::sql_query(ENQUEUE_SQL)
+        .bind::<Array<Text>, _>(digests)
+        .bind::<Array<BigInt>, _>(indexes)
+        .execute(conn)
+        .await?)
+}
+
+/// Claim up to `CLAIM_BATCH_SIZE` due deliveries with fewer than `max_attempts` attempts for
+/// `claim_secs`
+async fn claim(
+    claim_secs: f64,
+    max_attempts: i32,
+    conn: &mut postgres::Connection<'_>,
+) -> Result<Vec<Delivery>> {
+    Ok(diesel::sql_query(CLAIM_SQL)
+        .bind::<BigInt, _>(CLAIM_BATCH_SIZE)
+        .bind::<Double, _>(claim_secs)
+        .bind::<Int4, _>(max_attempts)
+        .load(conn)
+        .await?)
+}
+
+/// Mark deliveries out of attempts as FAILED, see `FAIL_EXHAUSTED_SQL`
+async fn fail_exhausted(max_attempts: i32, conn: &mut postgres::Connection<'_>) -> Result<usize> {
+    Ok(diesel::sql_query(FAIL_EXHAUSTED_SQL)
+        .bind::<Int4, _>(max_attempts)
+        .execute(conn)
+        .await?)
+}
+
+/// A claimed delivery with its endpoint
+#[derive(QueryableByName, Debug, Clone)]
+struct Delivery {
+    #[diesel(sql_type = Int8)]
+    id: i64,
+    #[diesel(sql_type = Text)]
+    event_type: String,
+    #[diesel(sql_type = Text)]
+    room_id: String,
+    #[diesel(sql_type = Jsonb)]
+    data: Value,
+    #[diesel(sql_type = Int4)]
+    attempts: i32,
+    #[diesel(sql_type = Int8)]
+    checkpoint_sequence_number: i64,
+    #[diesel(sql_type = Int8)]
+    checkpoint_timestamp_ms: i64,
+    #[diesel(sql_type = Text)]
+    transaction_digest: String,
+    #[diesel(sql_type = Int8)]
+    event_index: i64,
+    #[diesel(sql_type = Text)]
+    url: String,
+    #[diesel(sql_type = Text)]
+    secret: String,
+}
+
+impl Delivery {
+    /// JSON request body; this exact byte string is what gets signed
+    fn body(&self) -> String {
+        serde_json::json!({
+            "delivery_id": self.id,
+            "event_type": self.event_type,
+            "room_id": self.room_id,
+            "data": self.data,
+            "checkpoint_sequence_number": self.checkpoint_sequence_number,
+            "checkpoint_timestamp_ms": self.checkpoint_timestamp_ms,
+            "transaction_digest": self.transaction_digest,
+            "event_index": self.event_index,
+        })
+        .to_string()
+    }
+}
+
+/// Hex HMAC-SHA256 of `"{timestamp}.{body}"` under the subscription secret, sent as
+/// `X-SuiMeet-Signature: sha256=<hex>`. Signing the timestamp lets receivers reject replays.
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Delay before retrying a delivery that has failed `attempts` times
+fn backoff(attempts: i32) -> Duration {
+    let doublings = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    BASE_BACKOFF.saturating_mul(1 << doublings).min(MAX_BACKOFF)
+}
+
+/// A failed request: the response status if there was one
+#[derive(Debug)]
+struct Failure {
+    status_code: Option<i32>,
+    error: String,
+}
+
+/// POST a delivery, succeeding on any 2xx response
+async fn post(client: &reqwest::Client, delivery: &Delivery) -> Result<i32, Failure> {
+    let body = delivery.body();
+    let timestamp = chrono::Utc::now().timestamp();
+
+    let response = client
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, &delivery.event_type)
+        .header(DELIVERY_HEADER, delivery.id)
+        .header(TIMESTAMP_HEADER, timestamp)
+        .header(SIGNATURE_HEADER, format!("sha256={}", signature(&delivery.secret, timestamp, &body)))
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| Failure {
+            status_code: None,
+            error: e.to_string(),
+        })?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16() as i32)
+    } else {
+        Err(Failure {
+            status_code: Some(status.as_u16() as i32),
+            error: format!("endpoint responded {status}"),
+        })
+    }
+}
+
+/// Record the outcome of one attempt. A delivery out of attempts is marked FAILED.
+async fn record(
+    delivery: &Delivery,
+    outcome: Result<i32, Failure>,
+    max_attempts: i32,
+    conn: &mut postgres::Connection<'_>,
+) -> Result<()> {
+    let row = webhook_deliveries::table.find(delivery.id);
+
+    match outcome {
+        Ok(status_code) => {
+            diesel::update(row)
+                .set((
+                    webhook_deliveries::status.eq("DELIVERED"),
+                    webhook_deliveries::last_status_code.eq(Some(status_code)),
+                    webhook_deliveries::last_error.eq(None::<String>),
+                    webhook_deliveries::delivered_at.eq(diesel::dsl::now.nullable()),
+                ))
+                .execute(conn)
+                .await?;
+        }
+        Err(failure) => {
+            let status = if delivery.attempts >= max_attempts { "FAILED" } else { "PENDING" };
+            let retry_in = backoff(delivery.attempts).as_millis() as i64;
+
+            diesel::update(row)
+                .set((
+                    webhook_deliveries::status.eq(status),
+                    webhook_deliveries::last_status_code.eq(failure.status_code),
+                    webhook_deliveries::last_error.eq(Some(failure.error)),
+                    webhook_deliveries::next_attempt_at.eq(diesel::dsl::now + retry_in.milliseconds()),
+                ))
+                .execute(conn)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Drain the webhook outbox until the process is stopped, checking for due deliveries every
+/// `poll_interval` once it is empty
+pub async fn deliver_webhooks(
+    db: Db,
+    poll_interval: Duration,
+    request_timeout: Duration,
+    max_attempts: i32,
+) -> Result<()> {
+    let client = reqwest::Client::builder().timeout(request_timeout).build()?;
+    // Long enough that a claim outlives its request
+    let claim_secs = (request_timeout * 2).as_secs_f64();
+
+    info!("Delivering webhooks");
+
+    loop {
+        let deliveries: Vec<Delivery> = match db.connect().await {
+            Ok(mut conn) => {
+                match fail_exhausted(max_attempts, &mut conn).await {
+                    Ok(0) => {}
+                    Ok(failed) => warn!("{failed} webhook deliveries ran out of attempts without a recorded outcome"),
+                    Err(e) => warn!("Failed to fail exhausted webhook deliveries: {e:#}"),
+                }
+
+                claim(claim_secs, max_attempts, &mut conn).await.unwrap_or_else(|e| {
+                    warn!("Failed to claim webhook deliveries: {e:#}");
+                    vec![]
+                })
+            }
+            Err(e) => {
+                warn!("Failed to connect for webhook deliveries: {e:#}");
+                vec![]
+            }
+        };
+
+        if deliveries.is_empty() {
+            tokio::time::sleep(poll_interval).await;
+            continue;
+        }
+
+        let outcomes = futures::future::join_all(deliveries.iter().map(|delivery| post(&client, delivery))).await;
+
+        // An unrecorded outcome is retried once the claim expires
+        let mut conn = match db.connect().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("Failed to connect to record webhook deliveries: {e:#}");
+                continue;
+            }
+        };
+        for (delivery, outcome) in deliveries.iter().zip(outcomes) {
+            if let Err(failure) = &outcome {
+                warn!(
+                    "Webhook delivery {} to {} failed (attempt {}): {}",
+                    delivery.id, delivery.url, delivery.attempts, failure.error
+                );
+            }
+
+            if let Err(e) = record(delivery, outcome, max_attempts, &mut conn).await {
+                warn!("Failed to record webhook delivery {}: {e:#}", delivery.id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post as post_route;
+    use axum::Router;
+    use tokio::sync::mpsc;
+
+    /// Local endpoint answering every request with `status`, passing on what it received
+    async fn stub(status: StatusCode) -> (String, mpsc::UnboundedReceiver<(HeaderMap, String)>) {
+        let (sender, requests) = mpsc::unbounded_channel();
+        let app = Router::new().route(
+            "/hook",
+            post_route(move |headers: HeaderMap, body: String| {
+                let sender = sender.clone();
+                async move {
+                    let _ = sender.send((headers, body));
+                    status
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        (format!("http://{address}/hook"), requests)
+    }
+
+    fn delivery(url: String) -> Delivery {
+        Delivery {
+            id: 7,
+            event_type: "RoomStarted".to_string(),
+            room_id: "0x1".to_string(),
+            data: serde_json::json!({ "room_id": "0x1", "started_at": 1_700_000_000_000u64 }),
+            attempts: 1,
+            checkpoint_sequence_number: 42,
+            checkpoint_timestamp_ms: 42_000,
+            transaction_digest: "tx42".to_string(),
+            event_index: 0,
+            url,
+            secret: "shh".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_post_signs_delivery() {
+        let (url, mut requests) = stub(StatusCode::NO_CONTENT).await;
+        let client = reqwest::Client::new();
+
+        assert_eq!(post(&client, &delivery(url)).await.unwrap(), 204);
+
+        let (headers, body) = requests.recv().await.unwrap();
+        let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+        assert_eq!(headers[SIGNATURE_HEADER], format!("sha256={}", signature("shh", timestamp, &body)));
+        assert_eq!(headers[EVENT_HEADER], "RoomStarted");
+        assert_eq!(headers[DELIVERY_HEADER], "7");
+
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["delivery_id"], 7);
+        assert_eq!(body["data"]["started_at"], 1_700_000_000_000u64);
+    }
+
+    #[tokio::test]
+    async fn test_post_fails_on_error_status() {
+        let (url, _requests) = stub(StatusCode::SERVICE_UNAVAILABLE).await;
+        let failure = post(&reqwest::Client::new(), &delivery(url)).await.unwrap_err();
+        assert_eq!(failure.status_code, Some(503));
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        assert_eq!(backoff(1), Duration::from_secs(10));
+        assert_eq!(backoff(2), Duration::from_secs(20));
+        assert_eq!(backoff(4), Duration::from_secs(80));
+        assert_eq!(backoff(40), MAX_BACKOFF);
+    }
+
+    /// The database at `TEST_DATABASE_URL`, migrated
+    #[cfg(feature = "integration-tests")]
+    async fn test_db() -> Db {
+        use sui_indexer_alt_framework::postgres::DbArgs;
+
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL is set");
+        let db = Db::for_write(url.parse().unwrap(), DbArgs::default()).await.unwrap();
+        db.run_migrations(Some(&crate::MIGRATIONS)).await.unwrap();
+        db
+    }
+
+    /// A connection in a transaction that is never committed. Claims reach every due delivery,
+    /// so whatever a test claims or fails besides its own rows is rolled back with them.
+    #[cfg(feature = "integration-tests")]
+    async fn test_connection(db: &Db) -> postgres::Connection<'_> {
+        use diesel_async::AsyncConnection;
+
+        let mut conn = db.connect().await.unwrap();
+        conn.begin_test_transaction().await.unwrap();
+        // Subscription times are compared with checkpoint timestamps whatever the session zone
+        diesel::sql_query("SET TIME ZONE 'Asia/Tokyo'").execute(&mut conn).await.unwrap();
+        conn
+    }
+
+    /// Index `room_id` with the given (address, role, status) rows in `room_participants`
+    #[cfg(feature = "integration-tests")]
+    async fn room_with_hosts(room_id: &str, hosts: &[(&str, &str, &str)], conn: &mut postgres::Connection<'_>) {
+        use crate::db::schema::{meeting_rooms, room_participants};
+
+        diesel::insert_into(meeting_rooms::table)
+            .values((
+                meeting_rooms::room_id.eq(room_id),
+                meeting_rooms::title.eq("Standup"),
+                meeting_rooms::seal_policy_id.eq(format!("{room_id}-policy")),
+                meeting_rooms::max_participants.eq(10),
+                meeting_rooms::created_at.eq(0),
+                meeting_rooms::checkpoint_sequence_number.eq(1),
+                meeting_rooms::transaction_digest.eq("tx1"),
+            ))
+            .execute(conn)
+            .await
+            .unwrap();
+
+        for (address, role, status) in hosts {
+            diesel::insert_into(room_participants::table)
+                .values((
+                    room_participants::room_id.eq(room_id),
+                    room_participants::participant_address.eq(*address),
+                    room_participants::role.eq(*role),
+                    room_participants::status.eq(*status),
+                ))
+                .execute(conn)
+                .await
+                .unwrap();
+        }
+    }
+
+    /// A subscription for the rooms `host` hosts
+    #[cfg(feature = "integration-tests")]
+    async fn host_subscription(url: &str, host: &str, conn: &mut postgres::Connection<'_>) -> i64 {
+        use crate::db::schema::webhook_subscriptions;
+
+        diesel::insert_into(webhook_subscriptions::table)
+            .values((
+                webhook_subscriptions::url.eq(url),
+                webhook_subscriptions::secret.eq("shh"),
+                webhook_subscriptions::host_address.eq(host),
+            ))
+            .returning(webhook_subscriptions::id)
+            .get_result(conn)
+            .await
+            .unwrap()
+    }
+
+    /// A host who transferred their cap away stops receiving the room's events, and the
+    /// address it went to starts, though no HostCapGranted event ever named it
+    #[cfg(feature = "integration-tests")]
+    #[tokio::test]
+    async fn test_host_subscriptions_follow_current_hosts() {
+        use crate::db::models::NewRoomEvent;
+        use crate::db::schema::room_events;
+
+        let db = test_db().await;
+        let mut conn = test_connection(&db).await;
+
+        let room_id = format!("0xroom-{}", chrono::Utc::now().timestamp_nanos_opt().unwrap());
+        let (creator, former, current) = ("0xc1", "0xf0", "0xc2");
+        room_with_hosts(
+            &room_id,
+            &[(creator, "HOST", "ACTIVE"), (former, "DELEGATED_HOST", "REVOKED"), (current, "DELEGATED_HOST", "ACTIVE")],
+            &mut conn,
+        )
+        .await;
+
+        let mut subscriptions = Vec::new();
+        for host in [creator, former, current] {
+            subscriptions.push(host_subscription("http://127.0.0.1:9/hook", host, &mut conn).await);
+        }
+
+        let digest = format!("hosts-{room_id}");
+        diesel::insert_into(room_events::table)
+            .values(NewRoomEvent {
+                checkpoint_sequence_number: 2,
+                checkpoint_timestamp_ms: chrono::Utc::now().timestamp_millis() + 60 * 1000,
+                transaction_digest: digest.clone(),
+                event_index: 0,
+                event_type: "RoomStarted".to_string(),
+                room_id: room_id.clone(),
+                sender: current.to_string(),
+                package_id: "0x2".to_string(),
+                data: serde_json::json!({ "room_id": room_id }),
+                package_version: Some(1),
+            })
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        assert_eq!(enqueue_deliveries(vec![(digest, 0)], &mut conn).await.unwrap(), 2);
+
+        let mut receivers: Vec<i64> = webhook_deliveries::table
+            .filter(webhook_deliveries::subscription_id.eq_any(&subscriptions))
+            .select(webhook_deliveries::subscription_id)
+            .load(&mut conn)
+            .await
+            .unwrap();
+        receivers.sort();
+        assert_eq!(receivers, vec![subscriptions[0], subscriptions[2]]);
+    }
+
+    /// The outbox round trip
+    #[cfg(feature = "integration-tests")]
+    #[tokio::test]
+    async fn test_outbox_retries_then_fails() {
+        use crate::db::models::NewRoomEvent;
+        use crate::db::schema::room_events;
+
+        let db = test_db().await;
+        let mut conn = test_connection(&db).await;
+
+        let (hook_url, mut requests) = stub(StatusCode::SERVICE_UNAVAILABLE).await;
+        let host = "0xb0b";
+        let digest = format!("outbox-{}", chrono::Utc::now().timestamp_nanos_opt().unwrap());
+        let room_id = format!("0xroom-{digest}");
+
+        room_with_hosts(&room_id, &[(host, "HOST", "ACTIVE")], &mut conn).await;
+        let subscription_id = host_subscription(&hook_url, host, &mut conn).await;
+
+        // The room was created before the subscription, and started after it
+        let event = |event_index: i64, event_type: &str, checkpoint_timestamp_ms: i64| NewRoomEvent {
+            checkpoint_sequence_number: 1,
+            checkpoint_timestamp_ms,
+            transaction_digest: digest.clone(),
+            event_index,
+            event_type: event_type.to_string(),
+            room_id: room_id.clone(),
+            sender: host.to_string(),
+            package_id: "0x2".to_string(),
+            data: serde_json::json!({ "room_id": room_id, "host": host }),
+            package_version: Some(1),
+        };
+        let started_ms = chrono::Utc::now().timestamp_millis() + 60 * 1000;
+        diesel::insert_into(room_events::table)
+            .values(vec![event(0, "RoomCreated", 0), event(1, "RoomStarted", started_ms)])
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let queued = enqueue_deliveries(vec![(digest.clone(), 0), (digest.clone(), 1)], &mut conn)
+            .await
+            .unwrap();
+        assert_eq!(queued, 1);
+
+        /// Claimed deliveries to this test's subscription
+        async fn claimed(subscription_id: i64, conn: &mut postgres::Connection<'_>) -> Vec<Delivery> {
+            let deliveries = claim(60.0, 2, conn).await.unwrap();
+            let ids: Vec<i64> = webhook_deliveries::table
+                .filter(webhook_deliveries::subscription_id.eq(subscription_id))
+                .select(webhook_deliveries::id)
+                .load(conn)
+                .await
+                .unwrap();
+            deliveries.into_iter().filter(|delivery| ids.contains(&delivery.id)).collect()
+        }
+
+        let client = reqwest::Client::new();
+        let row = webhook_deliveries::table
+            .filter(webhook_deliveries::subscription_id.eq(subscription_id))
+            .select((webhook_deliveries::status, webhook_deliveries::attempts, webhook_deliveries::last_status_code));
+
+        // First attempt fails and backs off
+        let first = claimed(subscription_id, &mut conn).await;
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].event_type, "RoomStarted");
+        record(&first[0], post(&client, &first[0]).await, 2, &mut conn).await.unwrap();
+        assert!(requests.recv().await.is_some());
+
+        let (status, attempts, status_code): (String, i32, Option<i32>) = row.get_result(&mut conn).await.unwrap();
+        assert_eq!((status.as_str(), attempts, status_code), ("PENDING", 1, Some(503)));
+        assert!(claimed(subscription_id, &mut conn).await.is_empty(), "backing off");
+
+        // Once due again, the last attempt fails for good
+        diesel::update(webhook_deliveries::table.find(first[0].id))
+            .set(webhook_deliveries::next_attempt_at.eq(diesel::dsl::now))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let second = claimed(subscription_id, &mut conn).await;
+        assert_eq!(second.len(), 1);
+        record(&second[0], post(&client, &second[0]).await, 2, &mut conn).await.unwrap();
+
+        let (status, attempts, _): (String, i32, Option<i32>) = row.get_result(&mut conn).await.unwrap();
+        assert_eq!((status.as_str(), attempts), ("FAILED", 2));
+
+        // A last attempt whose outcome went unrecorded fails once its claim expires
+        diesel::update(webhook_deliveries::table.find(first[0].id))
+            .set((
+                webhook_deliveries::status.eq("PENDING"),
+                webhook_deliveries::next_attempt_at.eq(diesel::dsl::now),
+            ))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        assert!(claimed(subscription_id, &mut conn).await.is_empty(), "out of attempts");
+        fail_exhausted(2, &mut conn).await.unwrap();
+
+        let (status, attempts, status_code): (String, i32, Option<i32>) = row.get_result(&mut conn).await.unwrap();
+        assert_eq!((status.as_str(), attempts, status_code), ("FAILED", 2, None));
+    }
+}